@@ -14,12 +14,18 @@ import {
     parseConfig,
     parseNft,
     parseClaim,
+    parseEpochSchedule,
 } from './parse';
 
 import {
     TokenRecordState,
     DelegateRole,
     ConfigRecord,
+    Epoch,
+    IndexCheckpoint,
+    NftRecord,
+    RewardCurveKind,
+    AccountType,
 } from './types';
 import * as tx from './transaction';
 import { initMinter } from './mint_nft';
@@ -58,19 +64,23 @@ async function getTime(): Promise<number> {
 }
 
 async function printAllClaimRecords(since: number | null = null) {
-    const filters: GetProgramAccountsFilter[] = [{ dataSize: 80 }];
+    // Accounts of other types may have the same size, so the account type is matched too
+    const filters: GetProgramAccountsFilter[] = [
+        { dataSize: 99 },
+        { memcmp: { offset: 1, bytes: bs58.encode(Buffer.from([AccountType.ClaimRecord])) } },
+    ];
     let response: GetProgramAccountsResponse;
 
     if (since != null) {
         const config = await parseConfig(connection, pda.findConfigPda()[0]);
-        const firstGen = since / config.generation_duration;
-        const lastGen = (await getTime()) / config.generation_duration;
-        const buf = Buffer.allocUnsafe(4);
+        const firstGen = Math.floor(since / config.generation_duration);
+        const lastGen = Math.floor((await getTime()) / config.generation_duration);
+        const buf = Buffer.allocUnsafe(8);
 
         const responses: GetProgramAccountsResponse[] = [];
         for (let gen = firstGen; gen <= lastGen; gen++) {
-            buf.writeInt32LE(gen);
-            filters[1] = { memcmp: { offset: 0, bytes: bs58.encode(buf) } };
+            buf.writeBigInt64LE(BigInt(gen));
+            filters[2] = { memcmp: { offset: 2, bytes: bs58.encode(buf) } };
             responses.push(await connection.getProgramAccounts(pid.GAIMIN_PFP, { filters }));
         }
 
@@ -85,19 +95,158 @@ async function printAllClaimRecords(since: number | null = null) {
     }));
 }
 
-async function estimateClaimReward(config: ConfigRecord, mint: PublicKey) {
+const REWARD_INDEX_SCALE = 1000000000n;
+const EARNED_SCALE = REWARD_INDEX_SCALE * 10000n * 10000n;
+
+// Mirrors `reward::curve_index` of the program
+function curveIndex(rate: IndexCheckpoint, at: number): bigint {
+    const elapsed = BigInt(Math.max(0, at - rate.claimable_from));
+    const duration = BigInt(rate.accumulation_duration);
+    const curve = rate.reward_curve;
+    switch (curve.kind) {
+        case RewardCurveKind.Linear:
+            return elapsed * REWARD_INDEX_SCALE / duration;
+        case RewardCurveKind.CliffLinear:
+            return elapsed < BigInt(curve.cliff) ? 0n : elapsed * REWARD_INDEX_SCALE / duration;
+        case RewardCurveKind.ExponentialDecay: {
+            const halfLife = BigInt(curve.half_life);
+            let halvings = elapsed / halfLife;
+            let remainder = elapsed % halfLife;
+            if (halvings > 62n) {
+                halvings = 62n;
+                remainder = halfLife;
+            }
+            return (halfLife * ((1n << (halvings + 1n)) - 2n) + remainder) * REWARD_INDEX_SCALE / (duration << halvings);
+        }
+        case RewardCurveKind.Tiered: {
+            let total = 0n;
+            curve.tiers.forEach((tier, i) => {
+                const startsAfter = BigInt(tier.starts_after);
+                const next = curve.tiers[i + 1];
+                const end = next == undefined ? elapsed : BigInt(Math.min(Number(elapsed), next.starts_after));
+                if (end > startsAfter) {
+                    total += tier.rate * (end - startsAfter);
+                }
+            });
+            return total * REWARD_INDEX_SCALE / duration;
+        }
+    }
+}
+
+// Mirrors `reward::latest_checkpoint` of the program
+function latestCheckpoint(config: ConfigRecord): IndexCheckpoint {
+    return {
+        at: config.index_updated_at,
+        index: config.reward_index,
+        claimable_from: config.claimable_from,
+        accrual_ends_at: config.accrual_ends_at || config.claimable_until,
+        accumulation_duration: config.accumulation_duration,
+        reward_curve: config.reward_curve,
+    };
+}
+
+// Mirrors `reward::index_since` of the program
+function indexSince(checkpoint: IndexCheckpoint, at: number): bigint {
+    const from = Math.min(checkpoint.at, checkpoint.accrual_ends_at);
+    const index = checkpoint.index
+        + curveIndex(checkpoint, Math.min(at, checkpoint.accrual_ends_at))
+        - curveIndex(checkpoint, from);
+    return index > 0n ? index : 0n;
+}
+
+// Mirrors `reward::reward_index` of the program
+function rewardIndex(config: ConfigRecord, at: number): bigint {
+    if (config.emission_rate != 0n) {
+        if (config.staked_count == 0n) {
+            return config.reward_index;
+        }
+        const until = Math.min(at, config.accrual_ends_at || config.claimable_until);
+        const elapsed = BigInt(Math.max(0, until - Math.max(config.index_updated_at, config.claimable_from)));
+        return config.reward_index + elapsed * config.emission_rate * REWARD_INDEX_SCALE / config.staked_count;
+    }
+    const previous = config.index_history.filter(checkpoint => checkpoint.at <= at).pop();
+    return indexSince(previous != undefined && at < config.index_updated_at ? previous : latestCheckpoint(config), at);
+}
+
+// Mirrors `reward::earned` of the program
+function earnedReward(config: ConfigRecord, epochs: Epoch[], nft: NftRecord, until: number): [bigint, bigint] {
+    const last = nft.last_claimed_at;
+    const settled = nft.reward_index ?? rewardIndex(config, last);
+    const indexAt = (at: number) => {
+        if (at <= last) {
+            return settled;
+        }
+        const index = rewardIndex(config, at);
+        return index > settled ? index : settled;
+    };
+
+    const tiers = config.boost_schedule.tiers;
+    const changes = [...new Set([
+        ...tiers.map(tier => nft.staked_since + tier.staked_for),
+        ...epochs.map(epoch => epoch.starts_at),
+    ])].filter(change => change > last && change < until).sort((a, b) => a - b);
+
+    const multiplier = (from: number) => {
+        const tier = tiers.filter(tier => nft.staked_since + tier.staked_for <= from).pop();
+        const epoch = epochs.filter(epoch => epoch.starts_at <= from).pop();
+        return BigInt(tier?.multiplier_bps ?? 10000) * BigInt(epoch?.rate_bps ?? 10000);
+    };
+
+    let total = 0n;
+    let partStartsAt = last;
+    let partStartIndex = settled;
+    for (const partEndsAt of [...changes, until]) {
+        const partEndIndex = indexAt(partEndsAt);
+        total += (partEndIndex - partStartIndex) * multiplier(partStartsAt);
+        partStartsAt = partEndsAt;
+        partStartIndex = partEndIndex;
+    }
+    total += epochs
+        .filter(epoch => epoch.starts_at >= last && epoch.starts_at < until)
+        .reduce((bonus, epoch) => bonus + epoch.initial_bonus * EARNED_SCALE, 0n);
+    return [total, partStartIndex];
+}
+
+async function estimateClaimReward(config: ConfigRecord, epochs: Epoch[], mint: PublicKey) {
     const now = await getTime();
 
-    const [last_claim, claimed_amount, total_amount] = await parseNft(connection, pda.findNftPda(mint)[0])
-        .then(nft => [nft.last_claimed_at, nft.claimed_amount, nft.total_amount])
-        .catch(err => [config.claimable_from, 0, config.initial_reward + config.accumulated_reward]);
+    // Rewards of NFTs that aren't staked by the wallet yet accrue once they are staked now. Rewards
+    // settled for another staker are forfeited
+    const staked_from = Math.max(now, config.claimable_from);
+    const nft: NftRecord = await parseNft(connection, pda.findNftPda(mint)[0])
+        .then(nft => nft.staked_since != 0 && nft.staker.equals(wallet.publicKey) ? nft : {
+            ...nft,
+            last_claimed_at: Math.max(nft.last_claimed_at, staked_from),
+            staked_since: now,
+            staker: wallet.publicKey,
+            reward_index: null,
+            reward_remainder: 0n,
+            pending_reward: nft.staker.equals(wallet.publicKey) ? nft.pending_reward : 0n,
+        })
+        .catch(err => ({
+            claimed_amount: 0n,
+            total_amount: config.initial_reward + config.accumulated_reward,
+            last_claimed_at: staked_from,
+            payer: wallet.publicKey,
+            staked_since: now,
+            staker: wallet.publicKey,
+            reward_index: null,
+            reward_remainder: 0n,
+            pending_reward: 0n,
+        }));
 
-    const stake_period = now - last_claim;
-    const base_reward = claimed_amount == 0 ? config.initial_reward : 0;
-    return Math.min(
-        base_reward + (stake_period / config.accumulation_duration),
-        total_amount - claimed_amount
-    ) / 100;
+    const accrual_ends_at = config.accrual_ends_at || config.claimable_until;
+    const accrued_until = Math.max(nft.last_claimed_at, Math.min(now, accrual_ends_at));
+    const settled = nft.claimed_amount + nft.pending_reward;
+    const base_reward = settled == 0n ? config.initial_reward : 0n;
+    const [earned] = earnedReward(config, epochs, nft, accrued_until);
+    const accrued = base_reward + (earned + nft.reward_remainder) / EARNED_SCALE;
+    const reward = nft.pending_reward + (accrued < nft.total_amount - settled ? accrued : nft.total_amount - settled);
+    let left = nft.total_amount - nft.claimed_amount;
+    if (config.emission_budget != 0n && config.emission_budget - config.claimed_reward < left) {
+        left = config.emission_budget > config.claimed_reward ? config.emission_budget - config.claimed_reward : 0n;
+    }
+    return Number(reward < left ? reward : left) / 10 ** config.reward_decimals;
 }
 
 const clusters = {
@@ -115,11 +264,15 @@ const bnbWallet = '0xC3Fc58A10056fDF37b0AAAE295Ef3C42609988B8';
 async function ensureConfigSet() {
     await parseConfig(connection, pda.findConfigPda()[0]).catch(_ =>
         tx.setConfig(connection, wallet, wallet.publicKey, {
+            campaign_id: 0n,
             claimable_from: new Date().getTime() / 1000,
-            accumulated_reward: 0.8 * 80000,
-            initial_reward: 0.2 * 80000,
+            claimable_until: new Date().getTime() / 1000 + 30 * 24 * 3600,
+            accumulated_reward: 64000n,
+            initial_reward: 16000n,
             total_accumulation_period: 90000,
             generation_duration: 5 * 60,
+            timelock_duration: 0,
+            reward_decimals: 2,
         }));
 }
 
@@ -127,13 +280,24 @@ async function ensureNftRecord(mint: PublicKey) {
     await parseNft(connection, pda.findNftPda(mint)[0]).catch(_ => tx.registerNft(connection, wallet, mint));
 }
 
-async function ensureLock(mint: PublicKey) {
+// Staking locks the token, so tokens locked by the wallet itself are unlocked first
+async function ensureUnlocked(mint: PublicKey) {
     const tokenPda = pda.findTokenAccountPda(mint, wallet.publicKey)[0];
     const tokenRecordPda = pda.findTokenRecordPda(mint, tokenPda)[0];
     const tokenRecord = await parseTokenRecord(connection, tokenRecordPda);
-    if (tokenRecord.state != TokenRecordState.Locked) {
-        console.log("Locking token...");
-        await tx.delegateAndLock(connection, wallet, [mint]);
+    if (tokenRecord.state == TokenRecordState.Locked && tokenRecord.delegate?.toString() == wallet.publicKey.toString()) {
+        console.log("Unlocking token...");
+        await tx.revokeAndUnlock(connection, wallet, [mint]);
+    }
+}
+
+async function ensureStaked(mint: PublicKey) {
+    const staked = await parseNft(connection, pda.findNftPda(mint)[0])
+        .then(nft => nft.staked_since != 0 && nft.staker.equals(wallet.publicKey), _ => false);
+    if (!staked) {
+        await ensureUnlocked(mint);
+        console.log("Staking token...");
+        await tx.stakeNfts(connection, wallet, [mint]);
     }
 }
 
@@ -178,10 +342,11 @@ async function ensureMintedNfts(num: number = 10): Promise<PublicKey[]> {
     const mints = await ensureMintedNfts(5);
     await ensureConfigSet();
     const config = await parseConfig(connection, pda.findConfigPda()[0]);
+    const epochs = await parseEpochSchedule(connection, pda.findEpochSchedulePda()[0]).catch(err => []);
 
     await Promise.all(mints.map(async mint => {
-        await ensureLock(mint);
-        const reward = await estimateClaimReward(config, mint);
+        await ensureStaked(mint);
+        const reward = await estimateClaimReward(config, epochs, mint);
         console.log(`${mint.toString()}: ${reward}`);
     }));
 
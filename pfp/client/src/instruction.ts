@@ -10,16 +10,58 @@ import * as pda from './pda';
 
 import {
     ConfigArgs,
+    Role,
+    ConfigChangeArgs,
+    BoostSchedule,
+    Epoch,
+    RewardCurve,
+    RewardCurveKind,
 } from './types';
 
 import * as PID from './pid';
 
 const GAIMIN_PFP_INSTRUCTIONS = {
     CONFIG: 0,
-    DELETE: 1,
     NFT: 2,
     CREATE_CLAIM: 3,
     CLAIM: 4,
+    SCHEDULE_CONFIG_CHANGE: 5,
+    PROPOSE_AUTHORITY: 6,
+    ACCEPT_AUTHORITY: 7,
+    SET_PAUSE: 8,
+    SET_ROLE: 9,
+    APPLY_CONFIG_CHANGE: 10,
+    CANCEL_CONFIG_CHANGE: 11,
+    SET_MULTISIG: 12,
+    MIGRATE: 13,
+    CLOSE_NFT_RECORD: 14,
+    CLOSE_CLAIM_RECORD: 15,
+    SET_EPOCH_SCHEDULE: 16,
+    STAKE: 17,
+    UNSTAKE: 18,
+    CLOSE_CONFIG: 19,
+    CLOSE_MULTISIG: 20,
+    CLOSE_EPOCH_SCHEDULE: 21,
+    SETTLE_CLAIM_RECORD: 22,
+};
+
+export const MAX_MULTISIG_SIGNERS = 10;
+
+export const MAX_REWARD_TIERS = 4;
+
+const REWARD_CURVE_LEN = 1 + 8 + 1 + MAX_REWARD_TIERS * 16;
+
+export const MAX_BOOST_TIERS = 4;
+
+const BOOST_SCHEDULE_LEN = 1 + MAX_BOOST_TIERS * 10;
+
+export const MAX_EPOCHS = 8;
+
+export const PAUSE_FLAGS = {
+    REGISTRATION: 1 << 0,
+    CREATE_CLAIM: 1 << 1,
+    CLAIM: 1 << 2,
+    STAKE: 1 << 3,
 };
 
 const MPL_INSTRUCTIONS = {
@@ -36,41 +78,370 @@ const MPL_DELEGATE_TYPES = {
 // Some accounts in mpl token metadata instructions are optional, use this account to omit them
 const MPL_EMPTY_ACCOUNT = {pubkey: PID.MPL_TOKEN_METADATA, isSigner: false, isWritable: false};
 
+// Omitted optional accounts of the program
+const GAIMIN_EMPTY_ACCOUNT = {pubkey: PID.GAIMIN_PFP, isSigner: false, isWritable: false};
+
+// Accounts used by stake and unstake instructions to lock and unlock the token. NFTs with a rule set
+// need the authorization rules accounts instead of the empty ones
+function tokenMetadataKeys(mint: PublicKey) {
+    return [
+        {pubkey: pda.findMetadataAccountPda(mint)[0], isSigner: false, isWritable: true},
+        {pubkey: pda.findMasterEditionAccountPda(mint)[0], isSigner: false, isWritable: false},
+        {pubkey: pda.findDelegatePda()[0], isSigner: false, isWritable: false},
+        {pubkey: SystemProgram.programId, isSigner: false, isWritable: false},
+        {pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isSigner: false, isWritable: false},
+        {pubkey: PID.TOKEN, isSigner: false, isWritable: false},
+        {pubkey: PID.MPL_TOKEN_METADATA, isSigner: false, isWritable: false},
+        GAIMIN_EMPTY_ACCOUNT,
+        GAIMIN_EMPTY_ACCOUNT,
+    ];
+}
+
 export function setConfigInstruction(signer: PublicKey, creator: PublicKey, config: ConfigArgs): TransactionInstruction {
-    const data = Buffer.allocUnsafe(1 + 5 * 4);
+    const data = Buffer.alloc(1 + 8 + 8 * 8 + 1 + REWARD_CURVE_LEN + 8 + BOOST_SCHEDULE_LEN + 8);
     data.writeInt8(GAIMIN_PFP_INSTRUCTIONS.CONFIG);
-    data.writeInt32LE(config.claimable_from, 1);
-    data.writeInt32LE(config.accumulated_reward, 5);
-    data.writeInt32LE(config.initial_reward, 9);
-    data.writeInt32LE(config.total_accumulation_period, 13);
-    data.writeInt32LE(config.generation_duration, 17);
+    data.writeBigUInt64LE(config.campaign_id, 1);
+    data.writeBigInt64LE(BigInt(config.claimable_from), 9);
+    data.writeBigInt64LE(BigInt(config.claimable_until), 17);
+    data.writeBigInt64LE(BigInt(config.accrual_ends_at ?? 0), 25);
+    data.writeBigUInt64LE(config.accumulated_reward, 33);
+    data.writeBigUInt64LE(config.initial_reward, 41);
+    data.writeBigInt64LE(BigInt(config.total_accumulation_period), 49);
+    data.writeBigInt64LE(BigInt(config.generation_duration), 57);
+    data.writeBigInt64LE(BigInt(config.timelock_duration), 65);
+    data.writeUInt8(config.reward_decimals, 73);
+    packRewardCurve(config.reward_curve ?? { kind: RewardCurveKind.Linear }, data, 74);
+    data.writeBigUInt64LE(config.emission_budget ?? 0n, 74 + REWARD_CURVE_LEN);
+    packBoostSchedule(config.boost_schedule ?? { tiers: [] }, data, 82 + REWARD_CURVE_LEN);
+    data.writeBigUInt64LE(config.emission_rate ?? 0n, 82 + REWARD_CURVE_LEN + BOOST_SCHEDULE_LEN);
 
     return new TransactionInstruction({
         data,
         keys: [
             {pubkey: signer, isSigner: true, isWritable: false},
             {pubkey: creator, isSigner: false, isWritable: false},
-            {pubkey: pda.findConfigPda()[0], isSigner: false, isWritable: true},
+            {pubkey: pda.findConfigPda(config.campaign_id)[0], isSigner: false, isWritable: true},
+            {pubkey: pda.findProgramDataPda()[0], isSigner: false, isWritable: false},
             {pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
         ],
         programId: PID.GAIMIN_PFP,
     });
 }
 
-export function deleteAccountInstruction(signer: PublicKey, acc: PublicKey): TransactionInstruction {
+function packRewardCurve(curve: RewardCurve, data: Buffer, offset: number) {
+    data.writeUInt8(curve.kind, offset);
+    switch (curve.kind) {
+        case RewardCurveKind.CliffLinear:
+            data.writeBigInt64LE(BigInt(curve.cliff), offset + 1);
+            break;
+        case RewardCurveKind.ExponentialDecay:
+            data.writeBigInt64LE(BigInt(curve.half_life), offset + 1);
+            break;
+        case RewardCurveKind.Tiered:
+            data.writeUInt8(curve.tiers.length, offset + 9);
+            curve.tiers.forEach((tier, i) => {
+                data.writeBigInt64LE(BigInt(tier.starts_after), offset + 10 + i * 16);
+                data.writeBigUInt64LE(tier.rate, offset + 18 + i * 16);
+            });
+            break;
+    }
+}
+
+function packBoostSchedule(schedule: BoostSchedule, data: Buffer, offset: number) {
+    data.writeUInt8(schedule.tiers.length, offset);
+    schedule.tiers.forEach((tier, i) => {
+        data.writeBigInt64LE(BigInt(tier.staked_for), offset + 1 + i * 10);
+        data.writeUInt16LE(tier.multiplier_bps, offset + 9 + i * 10);
+    });
+}
+
+export function scheduleConfigChangeInstruction(signer: PublicKey, payer: PublicKey, change: ConfigChangeArgs, campaignId: bigint = 0n): TransactionInstruction {
+    const data = Buffer.alloc(1 + (1 + 32) + 8 * (1 + 8) + (1 + REWARD_CURVE_LEN) + (1 + 8) + (1 + BOOST_SCHEDULE_LEN) + (1 + 8));
+    data.writeInt8(GAIMIN_PFP_INSTRUCTIONS.SCHEDULE_CONFIG_CHANGE);
+
+    let offset = 1;
+    if (change.creator != undefined) {
+        data.writeInt8(1, offset);
+        change.creator.toBuffer().copy(data, offset + 1);
+    }
+    offset += 1 + 32;
+
+    for (const value of [
+        change.claimable_from,
+        change.claimable_until,
+        change.accrual_ends_at,
+        change.accumulated_reward,
+        change.initial_reward,
+        change.accumulation_duration,
+        change.generation_duration,
+        change.timelock_duration,
+    ]) {
+        if (value != undefined) {
+            data.writeInt8(1, offset);
+            data.writeBigInt64LE(BigInt(value), offset + 1);
+        }
+        offset += 1 + 8;
+    }
+
+    if (change.reward_curve != undefined) {
+        data.writeInt8(1, offset);
+        packRewardCurve(change.reward_curve, data, offset + 1);
+    }
+    offset += 1 + REWARD_CURVE_LEN;
+
+    if (change.emission_budget != undefined) {
+        data.writeInt8(1, offset);
+        data.writeBigUInt64LE(change.emission_budget, offset + 1);
+    }
+    offset += 1 + 8;
+
+    if (change.boost_schedule != undefined) {
+        data.writeInt8(1, offset);
+        packBoostSchedule(change.boost_schedule, data, offset + 1);
+    }
+    offset += 1 + BOOST_SCHEDULE_LEN;
+
+    if (change.emission_rate != undefined) {
+        data.writeInt8(1, offset);
+        data.writeBigUInt64LE(change.emission_rate, offset + 1);
+    }
+
+    return new TransactionInstruction({
+        data,
+        keys: [
+            {pubkey: signer, isSigner: true, isWritable: false},
+            {pubkey: payer, isSigner: true, isWritable: true},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: false},
+            {pubkey: pda.findConfigChangePda(campaignId)[0], isSigner: false, isWritable: true},
+            {pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+        ],
+        programId: PID.GAIMIN_PFP,
+    });
+}
+
+export function applyConfigChangeInstruction(receiver: PublicKey, campaignId: bigint = 0n): TransactionInstruction {
+    return new TransactionInstruction({
+        data: Buffer.from(Uint8Array.of(GAIMIN_PFP_INSTRUCTIONS.APPLY_CONFIG_CHANGE)),
+        keys: [
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: true},
+            {pubkey: pda.findConfigChangePda(campaignId)[0], isSigner: false, isWritable: true},
+            {pubkey: receiver, isSigner: false, isWritable: true},
+        ],
+        programId: PID.GAIMIN_PFP,
+    });
+}
+
+export function cancelConfigChangeInstruction(signer: PublicKey, receiver: PublicKey, campaignId: bigint = 0n): TransactionInstruction {
+    return new TransactionInstruction({
+        data: Buffer.from(Uint8Array.of(GAIMIN_PFP_INSTRUCTIONS.CANCEL_CONFIG_CHANGE)),
+        keys: [
+            {pubkey: signer, isSigner: true, isWritable: false},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: false},
+            {pubkey: pda.findConfigChangePda(campaignId)[0], isSigner: false, isWritable: true},
+            {pubkey: receiver, isSigner: false, isWritable: true},
+        ],
+        programId: PID.GAIMIN_PFP,
+    });
+}
+
+export function proposeAuthorityInstruction(signer: PublicKey, newAuthority: PublicKey, campaignId: bigint = 0n): TransactionInstruction {
+    return new TransactionInstruction({
+        data: Buffer.from(Uint8Array.of(GAIMIN_PFP_INSTRUCTIONS.PROPOSE_AUTHORITY)),
+        keys: [
+            {pubkey: signer, isSigner: true, isWritable: false},
+            {pubkey: newAuthority, isSigner: false, isWritable: false},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: true},
+        ],
+        programId: PID.GAIMIN_PFP,
+    });
+}
+
+export function acceptAuthorityInstruction(newAuthority: PublicKey, campaignId: bigint = 0n): TransactionInstruction {
+    return new TransactionInstruction({
+        data: Buffer.from(Uint8Array.of(GAIMIN_PFP_INSTRUCTIONS.ACCEPT_AUTHORITY)),
+        keys: [
+            {pubkey: newAuthority, isSigner: true, isWritable: false},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: true},
+        ],
+        programId: PID.GAIMIN_PFP,
+    });
+}
+
+export function setPauseInstruction(signer: PublicKey, paused: number, campaignId: bigint = 0n): TransactionInstruction {
     return new TransactionInstruction({
-        data: Buffer.from(Uint8Array.of(GAIMIN_PFP_INSTRUCTIONS.DELETE)),
+        data: Buffer.from(Uint8Array.of(GAIMIN_PFP_INSTRUCTIONS.SET_PAUSE, paused)),
         keys: [
             {pubkey: signer, isSigner: true, isWritable: false},
-            {pubkey: acc, isSigner: false, isWritable: true},
-            {pubkey: signer, isSigner: false, isWritable: true},
-            {pubkey: pda.findConfigPda()[0], isSigner: false, isWritable: false},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: true},
         ],
         programId: PID.GAIMIN_PFP,
     });
 }
 
-export function registerNftInstruction(signer: PublicKey, mint: PublicKey): TransactionInstruction {
+export function setRoleInstruction(signer: PublicKey, role: Role, account: PublicKey, campaignId: bigint = 0n): TransactionInstruction {
+    return new TransactionInstruction({
+        data: Buffer.from(Uint8Array.of(GAIMIN_PFP_INSTRUCTIONS.SET_ROLE, role)),
+        keys: [
+            {pubkey: signer, isSigner: true, isWritable: false},
+            {pubkey: account, isSigner: false, isWritable: false},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: true},
+        ],
+        programId: PID.GAIMIN_PFP,
+    });
+}
+
+export function setMultisigInstruction(signer: PublicKey, payer: PublicKey, threshold: number, signers: PublicKey[], campaignId: bigint = 0n): TransactionInstruction {
+    const data = Buffer.alloc(1 + 1 + 1 + MAX_MULTISIG_SIGNERS * 32);
+    data.writeUInt8(GAIMIN_PFP_INSTRUCTIONS.SET_MULTISIG);
+    data.writeUInt8(threshold, 1);
+    data.writeUInt8(signers.length, 2);
+    signers.forEach((s, i) => s.toBuffer().copy(data, 3 + i * 32));
+
+    return new TransactionInstruction({
+        data,
+        keys: [
+            {pubkey: signer, isSigner: true, isWritable: false},
+            {pubkey: payer, isSigner: true, isWritable: true},
+            {pubkey: pda.findMultisigPda(campaignId)[0], isSigner: false, isWritable: true},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: false},
+            {pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+        ],
+        programId: PID.GAIMIN_PFP,
+    });
+}
+
+export function setEpochScheduleInstruction(signer: PublicKey, payer: PublicKey, epochs: Epoch[], campaignId: bigint = 0n): TransactionInstruction {
+    const data = Buffer.alloc(1 + 1 + MAX_EPOCHS * 20);
+    data.writeUInt8(GAIMIN_PFP_INSTRUCTIONS.SET_EPOCH_SCHEDULE);
+    data.writeUInt8(epochs.length, 1);
+    epochs.forEach((epoch, i) => {
+        data.writeBigInt64LE(BigInt(epoch.starts_at), 2 + i * 20);
+        data.writeUInt32LE(epoch.rate_bps, 10 + i * 20);
+        data.writeBigUInt64LE(epoch.initial_bonus, 14 + i * 20);
+    });
+
+    return new TransactionInstruction({
+        data,
+        keys: [
+            {pubkey: signer, isSigner: true, isWritable: false},
+            {pubkey: payer, isSigner: true, isWritable: true},
+            {pubkey: pda.findEpochSchedulePda(campaignId)[0], isSigner: false, isWritable: true},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: false},
+            {pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+        ],
+        programId: PID.GAIMIN_PFP,
+    });
+}
+
+// Makes an instruction signed by the config authority or a role holder executable by the campaign
+// multisig. The multisig replaces the signer at `index`, or is appended as a remaining account if
+// there is none (NFT registration), followed by its signers
+export function withMultisigSigners(ix: TransactionInstruction, signers: PublicKey[], campaignId: bigint = 0n, index: number | undefined = 0): TransactionInstruction {
+    const multisig = {pubkey: pda.findMultisigPda(campaignId)[0], isSigner: false, isWritable: false};
+    if (index == undefined) {
+        ix.keys.push(multisig);
+    } else {
+        ix.keys[index] = multisig;
+    }
+    ix.keys.push(...signers.map(pubkey => ({pubkey, isSigner: true, isWritable: false})));
+    return ix;
+}
+
+export function migrateInstruction(signer: PublicKey, payer: PublicKey, target: PublicKey, campaignId: bigint = 0n): TransactionInstruction {
+    return new TransactionInstruction({
+        data: Buffer.from(Uint8Array.of(GAIMIN_PFP_INSTRUCTIONS.MIGRATE)),
+        keys: [
+            {pubkey: signer, isSigner: true, isWritable: false},
+            {pubkey: payer, isSigner: true, isWritable: true},
+            {pubkey: target, isSigner: false, isWritable: true},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: true},
+            {pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+        ],
+        programId: PID.GAIMIN_PFP,
+    });
+}
+
+export function closeConfigInstruction(signer: PublicKey, receiver: PublicKey, campaignId: bigint = 0n): TransactionInstruction {
+    return new TransactionInstruction({
+        data: Buffer.from(Uint8Array.of(GAIMIN_PFP_INSTRUCTIONS.CLOSE_CONFIG)),
+        keys: [
+            {pubkey: signer, isSigner: true, isWritable: false},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: true},
+            {pubkey: receiver, isSigner: false, isWritable: true},
+            {pubkey: pda.findConfigChangePda(campaignId)[0], isSigner: false, isWritable: false},
+            {pubkey: pda.findEpochSchedulePda(campaignId)[0], isSigner: false, isWritable: false},
+            {pubkey: pda.findMultisigPda(campaignId)[0], isSigner: false, isWritable: true},
+        ],
+        programId: PID.GAIMIN_PFP,
+    });
+}
+
+export function closeMultisigInstruction(signer: PublicKey, receiver: PublicKey, campaignId: bigint = 0n): TransactionInstruction {
+    return new TransactionInstruction({
+        data: Buffer.from(Uint8Array.of(GAIMIN_PFP_INSTRUCTIONS.CLOSE_MULTISIG)),
+        keys: [
+            {pubkey: signer, isSigner: true, isWritable: false},
+            {pubkey: pda.findMultisigPda(campaignId)[0], isSigner: false, isWritable: true},
+            {pubkey: receiver, isSigner: false, isWritable: true},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: false},
+        ],
+        programId: PID.GAIMIN_PFP,
+    });
+}
+
+export function closeEpochScheduleInstruction(signer: PublicKey, receiver: PublicKey, campaignId: bigint = 0n): TransactionInstruction {
+    return new TransactionInstruction({
+        data: Buffer.from(Uint8Array.of(GAIMIN_PFP_INSTRUCTIONS.CLOSE_EPOCH_SCHEDULE)),
+        keys: [
+            {pubkey: signer, isSigner: true, isWritable: false},
+            {pubkey: pda.findEpochSchedulePda(campaignId)[0], isSigner: false, isWritable: true},
+            {pubkey: receiver, isSigner: false, isWritable: true},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: false},
+        ],
+        programId: PID.GAIMIN_PFP,
+    });
+}
+
+export function closeNftRecordInstruction(signer: PublicKey, mint: PublicKey, receiver: PublicKey, campaignId: bigint = 0n): TransactionInstruction {
+    return new TransactionInstruction({
+        data: Buffer.from(Uint8Array.of(GAIMIN_PFP_INSTRUCTIONS.CLOSE_NFT_RECORD)),
+        keys: [
+            {pubkey: signer, isSigner: true, isWritable: false},
+            {pubkey: mint, isSigner: false, isWritable: false},
+            {pubkey: pda.findNftPda(mint, campaignId)[0], isSigner: false, isWritable: true},
+            {pubkey: receiver, isSigner: false, isWritable: true},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: true},
+        ],
+        programId: PID.GAIMIN_PFP,
+    });
+}
+
+export function closeClaimRecordInstruction(signer: PublicKey, claim: PublicKey, owner: PublicKey, campaignId: bigint = 0n): TransactionInstruction {
+    return new TransactionInstruction({
+        data: Buffer.from(Uint8Array.of(GAIMIN_PFP_INSTRUCTIONS.CLOSE_CLAIM_RECORD)),
+        keys: [
+            {pubkey: signer, isSigner: true, isWritable: false},
+            {pubkey: claim, isSigner: false, isWritable: true},
+            {pubkey: owner, isSigner: false, isWritable: true},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: false},
+        ],
+        programId: PID.GAIMIN_PFP,
+    });
+}
+
+export function settleClaimRecordInstruction(signer: PublicKey, claim: PublicKey, campaignId: bigint = 0n): TransactionInstruction {
+    return new TransactionInstruction({
+        data: Buffer.from(Uint8Array.of(GAIMIN_PFP_INSTRUCTIONS.SETTLE_CLAIM_RECORD)),
+        keys: [
+            {pubkey: signer, isSigner: true, isWritable: false},
+            {pubkey: claim, isSigner: false, isWritable: true},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: false},
+        ],
+        programId: PID.GAIMIN_PFP,
+    });
+}
+
+export function registerNftInstruction(signer: PublicKey, mint: PublicKey, campaignId: bigint = 0n): TransactionInstruction {
     return new TransactionInstruction({
         data: Buffer.from(Uint8Array.of(GAIMIN_PFP_INSTRUCTIONS.NFT)),
         keys: [
@@ -78,36 +449,36 @@ export function registerNftInstruction(signer: PublicKey, mint: PublicKey): Tran
             {pubkey: mint, isSigner: false, isWritable: false},
             {pubkey: pda.findMetadataAccountPda(mint)[0], isSigner: false, isWritable: false},
             {pubkey: pda.findMasterEditionAccountPda(mint)[0], isSigner: false, isWritable: false},
-            {pubkey: pda.findNftPda(mint)[0], isSigner: false, isWritable: true},
-            {pubkey: pda.findConfigPda()[0], isSigner: false, isWritable: false},
+            {pubkey: pda.findNftPda(mint, campaignId)[0], isSigner: false, isWritable: true},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: true},
             {pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
         ],
         programId: PID.GAIMIN_PFP,
     });
 }
 
-export function createClaimInstruction(wallet: PublicKey, bnbWallet: string, claim_seed: Buffer): TransactionInstruction {
+export function createClaimInstruction(wallet: PublicKey, bnbWallet: string, claim_seed: Buffer, campaignId: bigint = 0n): TransactionInstruction {
     const enc = new TextEncoder();
-    const [claim, bump] = pda.findClaimPda(wallet, claim_seed);
+    const [claim, bump] = pda.findClaimPda(wallet, claim_seed, campaignId);
 
     return new TransactionInstruction({
         data: Buffer.from(Uint8Array.of(GAIMIN_PFP_INSTRUCTIONS.CREATE_CLAIM, bump, ...claim_seed, ...enc.encode(bnbWallet.slice(2)))),
         keys: [
             {pubkey: wallet, isSigner: true, isWritable: false},
             {pubkey: claim, isSigner: false, isWritable: true},
-            {pubkey: pda.findConfigPda()[0], isSigner: false, isWritable: false},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: false},
             {pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
         ],
         programId: PID.GAIMIN_PFP,
     });
 }
 
-export function claimInstruction(wallet: PublicKey, mint: PublicKey, claim_seed: Buffer): TransactionInstruction {
-    const [nft_record, nft_bump] = pda.findNftPda(mint);
+export function claimInstruction(wallet: PublicKey, mint: PublicKey, claim_seed: Buffer, campaignId: bigint = 0n): TransactionInstruction {
+    const [nft_record, nft_bump] = pda.findNftPda(mint, campaignId);
     const [token, token_bump] = pda.findTokenAccountPda(mint, wallet);
-    const config = pda.findConfigPda()[0];
+    const config = pda.findConfigPda(campaignId)[0];
     const [token_record, token_record_bump] = pda.findTokenRecordPda(mint, token);
-    const claim = pda.findClaimPda(wallet, claim_seed)[0];
+    const claim = pda.findClaimPda(wallet, claim_seed, campaignId)[0];
 
     return new TransactionInstruction({
         data: Buffer.from(Uint8Array.of(
@@ -122,7 +493,54 @@ export function claimInstruction(wallet: PublicKey, mint: PublicKey, claim_seed:
             {pubkey: token_record, isSigner: false, isWritable: false},
             {pubkey: nft_record, isSigner: false, isWritable: true},
             {pubkey: claim, isSigner: false, isWritable: true},
-            {pubkey: config, isSigner: false, isWritable: false},
+            {pubkey: config, isSigner: false, isWritable: true},
+            {pubkey: pda.findEpochSchedulePda(campaignId)[0], isSigner: false, isWritable: false},
+        ],
+        programId: PID.GAIMIN_PFP,
+    });
+}
+
+export function stakeInstruction(wallet: PublicKey, mint: PublicKey, campaignId: bigint = 0n): TransactionInstruction {
+    const [nft_record, nft_bump] = pda.findNftPda(mint, campaignId);
+    const [token, token_bump] = pda.findTokenAccountPda(mint, wallet);
+    const [token_record, token_record_bump] = pda.findTokenRecordPda(mint, token);
+
+    return new TransactionInstruction({
+        data: Buffer.from(Uint8Array.of(
+            GAIMIN_PFP_INSTRUCTIONS.STAKE,
+            token_bump,
+            token_record_bump,
+            nft_bump,
+        )),
+        keys: [
+            {pubkey: wallet, isSigner: true, isWritable: true},
+            {pubkey: token, isSigner: false, isWritable: true},
+            {pubkey: token_record, isSigner: false, isWritable: true},
+            {pubkey: nft_record, isSigner: false, isWritable: true},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: true},
+            {pubkey: mint, isSigner: false, isWritable: false},
+            {pubkey: pda.findStakeLockPda(mint)[0], isSigner: false, isWritable: true},
+            ...tokenMetadataKeys(mint),
+        ],
+        programId: PID.GAIMIN_PFP,
+    });
+}
+
+export function unstakeInstruction(wallet: PublicKey, mint: PublicKey, campaignId: bigint = 0n): TransactionInstruction {
+    const token = pda.findTokenAccountPda(mint, wallet)[0];
+
+    return new TransactionInstruction({
+        data: Buffer.from(Uint8Array.of(GAIMIN_PFP_INSTRUCTIONS.UNSTAKE)),
+        keys: [
+            {pubkey: wallet, isSigner: true, isWritable: true},
+            {pubkey: mint, isSigner: false, isWritable: false},
+            {pubkey: pda.findNftPda(mint, campaignId)[0], isSigner: false, isWritable: true},
+            {pubkey: pda.findConfigPda(campaignId)[0], isSigner: false, isWritable: true},
+            {pubkey: token, isSigner: false, isWritable: true},
+            {pubkey: pda.findTokenRecordPda(mint, token)[0], isSigner: false, isWritable: true},
+            {pubkey: pda.findStakeLockPda(mint)[0], isSigner: false, isWritable: true},
+            {pubkey: pda.findEpochSchedulePda(campaignId)[0], isSigner: false, isWritable: false},
+            ...tokenMetadataKeys(mint),
         ],
         programId: PID.GAIMIN_PFP,
     });
@@ -7,8 +7,12 @@ import {
 import {
     TokenRecord,
     ConfigRecord,
+    MultisigRecord,
     NftRecord,
     ClaimRecord,
+    Epoch,
+    RewardCurve,
+    RewardCurveKind,
 } from './types';
 
 const bs58 = require("bs58");
@@ -34,23 +38,112 @@ export function parseTokenRecord(connection: Connection, acc: PublicKey): Promis
 export function parseConfig(connection: Connection, acc: PublicKey): Promise<ConfigRecord> {
     return getAccountInfo(connection, acc).then(resp => {
         return {
-            authority: new PublicKey(bs58.encode(resp.data.slice(0, 32))),
-            creator: new PublicKey(bs58.encode(resp.data.slice(32, 64))),
-            claimable_from: resp.data.readInt32LE(64),
-            accumulated_reward: resp.data.readInt32LE(68),
-            initial_reward: resp.data.readInt32LE(72),
-            accumulation_duration: resp.data.readInt32LE(76),
-            generation_duration: resp.data.readInt32LE(80),
+            campaign_id: resp.data.readBigUInt64LE(2),
+            authority: new PublicKey(bs58.encode(resp.data.slice(10, 42))),
+            pending_authority: new PublicKey(bs58.encode(resp.data.slice(42, 74))),
+            creator: new PublicKey(bs58.encode(resp.data.slice(74, 106))),
+            registrar: new PublicKey(bs58.encode(resp.data.slice(106, 138))),
+            settler: new PublicKey(bs58.encode(resp.data.slice(138, 170))),
+            claimable_from: Number(resp.data.readBigInt64LE(170)),
+            claimable_until: Number(resp.data.readBigInt64LE(178)),
+            accrual_ends_at: Number(resp.data.readBigInt64LE(186)),
+            accumulated_reward: resp.data.readBigUInt64LE(194),
+            initial_reward: resp.data.readBigUInt64LE(202),
+            accumulation_duration: Number(resp.data.readBigInt64LE(210)),
+            generation_duration: Number(resp.data.readBigInt64LE(218)),
+            timelock_duration: Number(resp.data.readBigInt64LE(226)),
+            reward_decimals: resp.data.readUInt8(234),
+            paused: resp.data.readUInt8(235),
+            nft_count: resp.data.readBigUInt64LE(236),
+            payer: new PublicKey(bs58.encode(resp.data.slice(244, 276))),
+            reward_curve: parseRewardCurve(resp.data, 276),
+            emission_budget: resp.data.readBigUInt64LE(350),
+            allocated_reward: resp.data.readBigUInt64LE(358),
+            claimed_reward: resp.data.readBigUInt64LE(366),
+            boost_schedule: {
+                tiers: [...Array(resp.data.readUInt8(374)).keys()].map(i => ({
+                    staked_for: Number(resp.data.readBigInt64LE(375 + i * 10)),
+                    multiplier_bps: resp.data.readUInt16LE(383 + i * 10),
+                })),
+            },
+            reward_index: resp.data.readBigUInt64LE(415) | (resp.data.readBigUInt64LE(423) << 64n),
+            index_updated_at: Number(resp.data.readBigInt64LE(431)),
+            emission_rate: resp.data.readBigUInt64LE(439),
+            staked_count: resp.data.readBigUInt64LE(447),
+            index_history: [...Array(resp.data.readUInt8(455)).keys()].map(i => 456 + i * 122).map(offset => ({
+                at: Number(resp.data.readBigInt64LE(offset)),
+                index: resp.data.readBigUInt64LE(offset + 8) | (resp.data.readBigUInt64LE(offset + 16) << 64n),
+                claimable_from: Number(resp.data.readBigInt64LE(offset + 24)),
+                accrual_ends_at: Number(resp.data.readBigInt64LE(offset + 32)),
+                accumulation_duration: Number(resp.data.readBigInt64LE(offset + 40)),
+                reward_curve: parseRewardCurve(resp.data, offset + 48),
+            })),
         };
     });
 }
 
+function parseRewardCurve(data: Buffer, offset: number): RewardCurve {
+    const kind: RewardCurveKind = data.readUInt8(offset);
+    const parameter = Number(data.readBigInt64LE(offset + 1));
+    switch (kind) {
+        case RewardCurveKind.CliffLinear:
+            return { kind, cliff: parameter };
+        case RewardCurveKind.ExponentialDecay:
+            return { kind, half_life: parameter };
+        case RewardCurveKind.Tiered:
+            return {
+                kind,
+                tiers: [...Array(data.readUInt8(offset + 9)).keys()].map(i => ({
+                    starts_after: Number(data.readBigInt64LE(offset + 10 + i * 16)),
+                    rate: data.readBigUInt64LE(offset + 18 + i * 16),
+                })),
+            };
+        default:
+            return { kind: RewardCurveKind.Linear };
+    }
+}
+
+export function parseMultisig(connection: Connection, acc: PublicKey): Promise<MultisigRecord> {
+    return getAccountInfo(connection, acc).then(resp => {
+        const count = resp.data.readUInt8(3);
+        return {
+            threshold: resp.data.readUInt8(2),
+            signers: [...Array(count).keys()].map(i => new PublicKey(bs58.encode(resp.data.slice(4 + i * 32, 36 + i * 32)))),
+        };
+    });
+}
+
+export function parseEpochSchedule(connection: Connection, acc: PublicKey): Promise<Epoch[]> {
+    return getAccountInfo(connection, acc).then(resp => {
+        return [...Array(resp.data.readUInt8(2)).keys()].map(i => ({
+            starts_at: Number(resp.data.readBigInt64LE(3 + i * 20)),
+            rate_bps: resp.data.readUInt32LE(11 + i * 20),
+            initial_bonus: resp.data.readBigUInt64LE(15 + i * 20),
+        }));
+    });
+}
+
+// Returns the IDs of the campaigns the NFT is staked in
+export function parseStakeLock(connection: Connection, acc: PublicKey): Promise<bigint[]> {
+    return getAccountInfo(connection, acc).then(resp => {
+        return [...Array(resp.data.readUInt8(2)).keys()].map(i => resp.data.readBigUInt64LE(3 + i * 8));
+    });
+}
+
 export function parseNft(connection: Connection, acc: PublicKey): Promise<NftRecord> {
     return getAccountInfo(connection, acc).then(resp => {
         return {
-            claimed_amount: resp.data.readInt32LE(0),
-            total_amount: resp.data.readInt32LE(4),
-            last_claimed_at: Number(resp.data.readInt32LE(8)),
+            claimed_amount: resp.data.readBigUInt64LE(2),
+            total_amount: resp.data.readBigUInt64LE(10),
+            last_claimed_at: Number(resp.data.readBigInt64LE(18)),
+            payer: new PublicKey(bs58.encode(resp.data.slice(26, 58))),
+            staked_since: Number(resp.data.readBigInt64LE(58)),
+            staker: new PublicKey(bs58.encode(resp.data.slice(66, 98))),
+            reward_index: resp.data.readUInt8(98) == 0
+                ? null
+                : resp.data.readBigUInt64LE(99) | (resp.data.readBigUInt64LE(107) << 64n),
+            reward_remainder: resp.data.readBigUInt64LE(115),
+            pending_reward: resp.data.readBigUInt64LE(123),
         };
     });
 }
@@ -58,10 +151,12 @@ export function parseNft(connection: Connection, acc: PublicKey): Promise<NftRec
 export function parseClaim(connection: Connection, acc: PublicKey): Promise<ClaimRecord> {
     return getAccountInfo(connection, acc).then(resp => {
         return {
-            generation: resp.data.readInt32LE(0),
-            amount: resp.data.readInt32LE(4),
-            owner: new PublicKey(bs58.encode(resp.data.slice(8, 40))),
-            bnb_chain_wallet_address: '0x' + resp.data.toString('utf8', 40)
+            generation: Number(resp.data.readBigInt64LE(2)),
+            amount: resp.data.readBigUInt64LE(10),
+            owner: new PublicKey(bs58.encode(resp.data.slice(18, 50))),
+            bnb_chain_wallet_address: '0x' + resp.data.toString('utf8', 50, 90),
+            campaign_id: resp.data.readBigUInt64LE(90),
+            settled: resp.data.readUInt8(98) != 0,
         };
     });
 }
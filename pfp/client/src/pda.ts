@@ -55,29 +55,102 @@ export function findTokenRecordPda(mint: PublicKey, token: PublicKey): [PublicKe
     );
 }
 
-export function findConfigPda(): [PublicKey, number] {
+export function findProgramDataPda(): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [
+            PID.GAIMIN_PFP.toBuffer(),
+        ],
+        PID.BPF_LOADER_UPGRADEABLE
+    );
+}
+
+// The default campaign (id 0) uses an empty seed to keep the addresses of its existing accounts
+export function campaignSeed(campaignId: bigint): Buffer {
+    if (campaignId == 0n) {
+        return Buffer.alloc(0);
+    }
+
+    const seed = Buffer.alloc(8);
+    seed.writeBigUInt64LE(campaignId);
+    return seed;
+}
+
+export function findConfigPda(campaignId: bigint = 0n): [PublicKey, number] {
     return PublicKey.findProgramAddressSync(
         [
             Buffer.from('config'),
+            campaignSeed(campaignId),
+        ],
+        PID.GAIMIN_PFP
+    );
+}
+
+export function findConfigChangePda(campaignId: bigint = 0n): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [
+            Buffer.from('config_change'),
+            campaignSeed(campaignId),
+        ],
+        PID.GAIMIN_PFP
+    );
+}
+
+export function findMultisigPda(campaignId: bigint = 0n): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [
+            Buffer.from('multisig'),
+            campaignSeed(campaignId),
+        ],
+        PID.GAIMIN_PFP
+    );
+}
+
+export function findEpochSchedulePda(campaignId: bigint = 0n): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [
+            Buffer.from('epoch_schedule'),
+            campaignSeed(campaignId),
+        ],
+        PID.GAIMIN_PFP
+    );
+}
+
+// The same delegate locks staked NFTs for every campaign
+export function findDelegatePda(): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [
+            Buffer.from('delegate'),
+        ],
+        PID.GAIMIN_PFP
+    );
+}
+
+export function findStakeLockPda(mint: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [
+            Buffer.from('stake_lock'),
+            mint.toBuffer(),
         ],
         PID.GAIMIN_PFP
     );
 }
 
-export function findNftPda(mint: PublicKey): [PublicKey, number] {
+export function findNftPda(mint: PublicKey, campaignId: bigint = 0n): [PublicKey, number] {
     return PublicKey.findProgramAddressSync(
         [
             Buffer.from('nft'),
+            campaignSeed(campaignId),
             mint.toBuffer(),
         ],
         PID.GAIMIN_PFP
     );
 }
 
-export function findClaimPda(wallet: PublicKey, seed: Buffer): [PublicKey, number] {
+export function findClaimPda(wallet: PublicKey, seed: Buffer, campaignId: bigint = 0n): [PublicKey, number] {
     return PublicKey.findProgramAddressSync(
         [
             Buffer.from('claim'),
+            campaignSeed(campaignId),
             wallet.toBuffer(),
             seed,
         ],
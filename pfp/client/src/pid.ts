@@ -4,3 +4,4 @@ export const TOKEN: PublicKey = new PublicKey('TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9
 export const SPL_ASSOCIATED_TOKEN_ACCOUNT: PublicKey = new PublicKey('ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL');
 export const MPL_TOKEN_METADATA: PublicKey = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s');
 export const GAIMIN_PFP: PublicKey = new PublicKey('GMRXrgb2TF6ejGt3nJrUAkwVoKUrnVK5LZ6duRE8x47g');
+export const BPF_LOADER_UPGRADEABLE: PublicKey = new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111');
@@ -13,6 +13,7 @@ import {
     parseClaim,
 } from './parse';
 
+import { AccountType } from './types';
 import * as tx from './transaction';
 import * as pid from './pid';
 
@@ -30,11 +31,15 @@ async function getTime(): Promise<number> {
 }
 
 async function printAllClaimRecords(generation: number | null = null) {
-    const filters: GetProgramAccountsFilter[] = [{ dataSize: 80 }];
+    // Accounts of other types may have the same size, so the account type is matched too
+    const filters: GetProgramAccountsFilter[] = [
+        { dataSize: 99 },
+        { memcmp: { offset: 1, bytes: bs58.encode(Buffer.from([AccountType.ClaimRecord])) } },
+    ];
     if (generation != null) {
-        const buf = Buffer.allocUnsafe(4);
-        buf.writeInt32LE(generation);
-        filters.push({ memcmp: { offset: 0, bytes: bs58.encode(buf) } });
+        const buf = Buffer.allocUnsafe(8);
+        buf.writeBigInt64LE(BigInt(generation));
+        filters.push({ memcmp: { offset: 2, bytes: bs58.encode(buf) } });
     }
 
     const response = await connection.getProgramAccounts(pid.GAIMIN_PFP, { filters });
@@ -52,11 +57,15 @@ const creator = new PublicKey('');
 (async () => {
     await parseConfig(connection, pda.findConfigPda()[0]).catch(_ =>
         tx.setConfig(connection, wallet, creator, {
+            campaign_id: 0n,
             claimable_from: 1711447200,
-            accumulated_reward: 0.8 * 41035 * 100,
-            initial_reward: 0.2 * 41035 * 100,
+            claimable_until: 1711447200 + 365 * 24 * 3600,
+            accumulated_reward: 3282800n,
+            initial_reward: 820700n,
             total_accumulation_period: 25920000,
             generation_duration: 3600,
+            timelock_duration: 2 * 24 * 3600,
+            reward_decimals: 2,
         }));
     const config = await parseConfig(connection, pda.findConfigPda()[0]);
 
@@ -1,6 +1,6 @@
 import * as crypto from "crypto";
 import { AddressLookupTableAccount, Connection, Keypair, PublicKey, TransactionMessage, TransactionMessageArgs, VersionedTransaction } from "@solana/web3.js";
-import { ConfigArgs, } from "./types";
+import { ConfigArgs, ConfigChangeArgs, Epoch, } from "./types";
 import * as ix from "./instruction";
 
 export async function sendAndConfirmTransaction(connection: Connection, signers: Keypair[], msg: Omit<TransactionMessageArgs, 'recentBlockhash'>, luts?: AddressLookupTableAccount[]) {
@@ -26,11 +26,56 @@ export async function setConfig(connection: Connection, authPayer: Keypair, crea
     });
 }
 
-export async function deleteAccount(connection: Connection, authPayer: Keypair, acc: PublicKey) {
+export async function scheduleConfigChange(connection: Connection, authPayer: Keypair, change: ConfigChangeArgs) {
     return await sendAndConfirmTransaction(connection, [authPayer], {
         payerKey: authPayer.publicKey,
         instructions: [
-            ix.deleteAccountInstruction(authPayer.publicKey, acc),
+            ix.scheduleConfigChangeInstruction(authPayer.publicKey, authPayer.publicKey, change),
+        ],
+    });
+}
+
+export async function applyConfigChange(connection: Connection, payer: Keypair, receiver: PublicKey) {
+    return await sendAndConfirmTransaction(connection, [payer], {
+        payerKey: payer.publicKey,
+        instructions: [
+            ix.applyConfigChangeInstruction(receiver),
+        ],
+    });
+}
+
+export async function setEpochSchedule(connection: Connection, authPayer: Keypair, epochs: Epoch[]) {
+    return await sendAndConfirmTransaction(connection, [authPayer], {
+        payerKey: authPayer.publicKey,
+        instructions: [
+            ix.setEpochScheduleInstruction(authPayer.publicKey, authPayer.publicKey, epochs),
+        ],
+    });
+}
+
+export async function closeNftRecord(connection: Connection, authPayer: Keypair, mint: PublicKey, receiver: PublicKey) {
+    return await sendAndConfirmTransaction(connection, [authPayer], {
+        payerKey: authPayer.publicKey,
+        instructions: [
+            ix.closeNftRecordInstruction(authPayer.publicKey, mint, receiver),
+        ],
+    });
+}
+
+export async function settleClaimRecord(connection: Connection, authPayer: Keypair, claim: PublicKey) {
+    return await sendAndConfirmTransaction(connection, [authPayer], {
+        payerKey: authPayer.publicKey,
+        instructions: [
+            ix.settleClaimRecordInstruction(authPayer.publicKey, claim),
+        ],
+    });
+}
+
+export async function closeClaimRecord(connection: Connection, authPayer: Keypair, claim: PublicKey, owner: PublicKey) {
+    return await sendAndConfirmTransaction(connection, [authPayer], {
+        payerKey: authPayer.publicKey,
+        instructions: [
+            ix.closeClaimRecordInstruction(authPayer.publicKey, claim, owner),
         ],
     });
 }
@@ -57,6 +102,25 @@ export async function claimReward(connection: Connection, userPayer: Keypair, mi
     }, luts);
 }
 
+export async function stakeNfts(connection: Connection, userPayer: Keypair, mints: PublicKey[], luts?: AddressLookupTableAccount[]) {
+    return await sendAndConfirmTransaction(connection, [userPayer], {
+        payerKey: userPayer.publicKey,
+        instructions: [
+            ...mints.map(mint => ix.registerNftInstruction(userPayer.publicKey, mint)),
+            ...mints.map(mint => ix.stakeInstruction(userPayer.publicKey, mint)),
+        ],
+    }, luts);
+}
+
+// Rewards accrued until now are settled and can still be claimed, unless another wallet stakes the
+// NFTs first
+export async function unstakeNfts(connection: Connection, userPayer: Keypair, mints: PublicKey[], luts?: AddressLookupTableAccount[]) {
+    return await sendAndConfirmTransaction(connection, [userPayer], {
+        payerKey: userPayer.publicKey,
+        instructions: mints.map(mint => ix.unstakeInstruction(userPayer.publicKey, mint)),
+    }, luts);
+}
+
 export async function delegateAndLock(connection: Connection, userPayer: Keypair, mints: PublicKey[], luts?: AddressLookupTableAccount[]) {
     return await sendAndConfirmTransaction(connection, [userPayer], {
         payerKey: userPayer.publicKey,
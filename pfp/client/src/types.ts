@@ -26,35 +26,168 @@ export type TokenRecord = {
     // ...
 };
 
+// Stored after the version byte of every program account
+export enum AccountType {
+    Uninitialized,
+    Config,
+    PendingConfigChange,
+    Multisig,
+    NftRecord,
+    ClaimRecord,
+    EpochSchedule,
+    StakeLock,
+}
+
+export enum Role {
+    Registrar,
+    Settler,
+}
+
+export enum RewardCurveKind {
+    Linear,
+    CliffLinear,
+    ExponentialDecay,
+    Tiered,
+}
+
+// Durations are measured from `claimable_from` and rates are in base units per accumulation duration
+export type RewardTier = {
+    starts_after: number;
+    rate: bigint;
+};
+
+export type RewardCurve =
+    | { kind: RewardCurveKind.Linear }
+    | { kind: RewardCurveKind.CliffLinear, cliff: number }
+    | { kind: RewardCurveKind.ExponentialDecay, half_life: number }
+    | { kind: RewardCurveKind.Tiered, tiers: RewardTier[] };
+
+// Multipliers are in basis points, so that 10000 equals 1.0x
+export type BoostTier = {
+    staked_for: number;
+    multiplier_bps: number;
+};
+
+export type BoostSchedule = {
+    tiers: BoostTier[];
+};
+
+export type Epoch = {
+    starts_at: number;
+    // Multiplier of the accrued rewards, 10000 keeps them unchanged
+    rate_bps: number;
+    initial_bonus: bigint;
+};
+
+// Rate of the reward index from a checkpoint until the next one
+export type IndexCheckpoint = {
+    at: number;
+    index: bigint;
+    claimable_from: number;
+    accrual_ends_at: number;
+    accumulation_duration: number;
+    reward_curve: RewardCurve;
+};
+
 export type ConfigRecord = {
+    campaign_id: bigint;
     authority: PublicKey;
+    pending_authority: PublicKey;
     creator: PublicKey;
+    registrar: PublicKey;
+    settler: PublicKey;
     claimable_from: number;
-    accumulated_reward: number;
-    initial_reward: number;
+    claimable_until: number;
+    accrual_ends_at: number;
+    accumulated_reward: bigint;
+    initial_reward: bigint;
     accumulation_duration: number;
     generation_duration: number;
+    timelock_duration: number;
+    reward_decimals: number;
+    paused: number;
+    nft_count: bigint;
+    payer: PublicKey;
+    reward_curve: RewardCurve;
+    emission_budget: bigint;
+    allocated_reward: bigint;
+    claimed_reward: bigint;
+    boost_schedule: BoostSchedule;
+    // Reward accrued by an NFT until `index_updated_at`, in billionths of a base unit
+    reward_index: bigint;
+    index_updated_at: number;
+    // Non-zero in pool mode
+    emission_rate: bigint;
+    staked_count: bigint;
+    // Earlier checkpoints of `reward_index`, oldest first
+    index_history: IndexCheckpoint[];
 };
 
 export type ConfigArgs = {
+    campaign_id: bigint;
     claimable_from: number;
-    accumulated_reward: number;
-    initial_reward: number;
+    claimable_until: number;
+    // Zero or omitted if rewards accrue until `claimable_until`
+    accrual_ends_at?: number;
+    accumulated_reward: bigint;
+    initial_reward: bigint;
     total_accumulation_period: number;
     generation_duration: number;
+    timelock_duration: number;
+    reward_decimals: number;
+    // Linear if omitted
+    reward_curve?: RewardCurve;
+    // Zero or omitted if the budget is unlimited
+    emission_budget?: bigint;
+    // No boost if omitted
+    boost_schedule?: BoostSchedule;
+    // Zero or omitted for fixed allowances per NFT, otherwise the reward emitted per second and
+    // shared by the staked NFTs. Can't be switched on or off later
+    emission_rate?: bigint;
+}
+
+export type ConfigChangeArgs = {
+    creator?: PublicKey;
+    claimable_from?: number;
+    claimable_until?: number;
+    accrual_ends_at?: number;
+    accumulated_reward?: bigint;
+    initial_reward?: bigint;
+    accumulation_duration?: number;
+    generation_duration?: number;
+    timelock_duration?: number;
+    reward_curve?: RewardCurve;
+    emission_budget?: bigint;
+    boost_schedule?: BoostSchedule;
+    emission_rate?: bigint;
 }
 
+export type MultisigRecord = {
+    threshold: number;
+    signers: PublicKey[];
+};
+
 export type NftRecord = {
-    claimed_amount: number;
-    total_amount: number;
+    claimed_amount: bigint;
+    total_amount: bigint;
     last_claimed_at: number;
+    payer: PublicKey;
+    // Zero if the NFT isn't staked. The staker is kept after unstaking to claim the pending reward
+    staked_since: number;
+    staker: PublicKey;
+    // Null for records created before the reward index existed
+    reward_index: bigint | null;
+    reward_remainder: bigint;
+    pending_reward: bigint;
 };
 
 export type ClaimRecord = {
     generation: number;
-    amount: number;
+    amount: bigint;
     owner: PublicKey;
     bnb_chain_wallet_address: string;
+    campaign_id: bigint;
+    settled: boolean;
 };
 
 export type MintRequest = {
@@ -68,6 +68,83 @@ pub enum GaiminError {
     ///
     /// Attempted to create an NFT record that wasn't created by the account specified in config
     InvalidCreator,
+
+    /// Error code `0xC`
+    ///
+    /// Attempted to create the config account with an authority that is not the upgrade authority
+    /// of the program
+    NotUpgradeAuthority,
+
+    /// Error code `0xD`
+    ///
+    /// Attempted to execute an operation that has been paused by the config authority
+    Paused,
+
+    /// Error code `0xE`
+    ///
+    /// Attempted to apply a scheduled config change before its timelock has expired
+    TimelockNotExpired,
+
+    /// Error code `0xF`
+    ///
+    /// Attempted to set up a multisig with duplicate signers, too many signers or a threshold that
+    /// cannot be met
+    InvalidMultisig,
+
+    /// Error code `0x10`
+    ///
+    /// Attempted to claim a reward for an NFT that has nothing left to claim because rewards
+    /// stopped accruing
+    AccrualFinished,
+
+    /// Error code `0x11`
+    ///
+    /// Attempted to claim a reward after the claiming window of the campaign has closed
+    ClaimingClosed,
+
+    /// Error code `0x12`
+    ///
+    /// Account holds a different type of data than the instruction expects
+    InvalidAccountType,
+
+    /// Error code `0x13`
+    ///
+    /// Attempted to close an account that is still in use, such as a config that still has NFT
+    /// records or a claim record whose generation hasn't ended
+    AccountInUse,
+
+    /// Error code `0x14`
+    ///
+    /// Attempted to register an NFT or claim a reward after the emission budget of the campaign
+    /// has been used up
+    BudgetExhausted,
+
+    /// Error code `0x15`
+    ///
+    /// Attempted to claim a reward for or unstake an NFT that isn't staked by the wallet
+    NotStaked,
+
+    /// Error code `0x16`
+    ///
+    /// Attempted to stake an NFT that is already staked by the wallet or another one
+    AlreadyStaked,
+
+    /// Error code `0x17`
+    ///
+    /// Attempted to stake or claim a reward for an NFT locked by a delegate other than the
+    /// delegate PDA of the program, e.g. a marketplace
+    ForeignLock,
+
+    /// Error code `0x18`
+    ///
+    /// Attempted to add a reward to or settle a claim record that has already been settled
+    ClaimSettled,
+
+    /// Error code `0x19`
+    ///
+    /// Attempted to stake an NFT in more campaigns at once than
+    /// [`crate::state::MAX_STAKED_CAMPAIGNS`]
+    TooManyCampaigns,
 }
 
 impl From<GaiminError> for ProgramError {
@@ -4,54 +4,69 @@ use solana_program::{
     msg,
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
 };
 use std::{fmt::Debug, mem};
 
 use crate::{
     error::GaiminError,
-    state::BNB_CHAIN_WALLET_ADDRESS_LENGTH,
-    utils::{assert_ix_data_length, parse_string},
+    state::{
+        BoostSchedule, Epoch, RewardCurve, Role, BNB_CHAIN_WALLET_ADDRESS_LENGTH, MAX_EPOCHS,
+        MAX_MULTISIG_SIGNERS,
+    },
+    utils::{assert_ix_data_length, pack_option, parse_string, unpack_option},
 };
 
 /// Instructions supported by the GMRX Claim Program
+///
+/// Wherever an instruction must be signed by the config authority or a role holder, that account
+/// may be a [`Multisig`](crate::state::Multisig) instead. The multisig is then passed without a
+/// signature, and at least [`Multisig::threshold`](crate::state::Multisig::threshold) of its
+/// signers must follow as signing remaining accounts.
 #[derive(Debug, ShankContext)]
 pub enum GaiminInstruction {
     /// Instruction code: `0x0`
     ///
-    /// Create and initialize the config account. It is a system instruction that can be executed
-    /// by anyone. The config account may NOT be initialized. The `authority` account will pay for
-    /// rent exemption and become the config authority with the exclusive right to execute other
-    /// system instructions. To update the config account, it must first be deleted and then
-    /// created again.
-    #[account(0, signer, name = "authority", desc = "Config authority/Rent payer")]
+    /// Create and initialize the config account of a campaign. It is a system instruction that
+    /// must be signed by the upgrade authority of the program. The config account may NOT be
+    /// initialized. The `authority` account will pay for rent exemption and become the config
+    /// authority with the exclusive right to execute other system instructions of the campaign.
+    /// To update the config account, use [`GaiminInstruction::ScheduleConfigChange`]. Every
+    /// campaign has its own NFT and claim records, so an NFT can be registered in multiple
+    /// campaigns and staked in up to [`crate::state::MAX_STAKED_CAMPAIGNS`] of them at once.
+    #[account(
+        0,
+        signer,
+        name = "authority",
+        desc = "Program upgrade authority/Rent payer"
+    )]
     #[account(1, name = "creator", desc = "Creator of claimable NFTs")]
     #[account(2, writable, name = "config", desc = "Config PDA")]
-    #[account(3, name = "system_program", desc = "System program")]
+    #[account(
+        3,
+        name = "program_data",
+        desc = "Program data account of this program"
+    )]
+    #[account(4, name = "system_program", desc = "System program")]
     Config(ConfigArgs),
 
-    /// Instruction code: `0x1`
-    ///
-    /// Delete an account owned by this program. It is a system instruction that must be signed by
-    /// the config authority. The config account must be initialized first. The account to be
-    /// deleted must also be initialized.
-    #[account(0, signer, name = "authority", desc = "Config authority")]
-    #[account(1, writable, name = "target", desc = "Account to be deleted")]
-    #[account(2, writable, name = "receiver", desc = "Account to send lamports to")]
-    #[account(3, name = "config", desc = "Config PDA")]
-    Delete,
-
     /// Instruction code: `0x2`
     ///
     /// Create and initialize an NFT record account. The config account must be initialized first.
-    /// The NFT record account may NOT be initialized. To update an existing NFT record, delete it
-    /// and create again. The NFT being registered must be valid and of the programmable standard.
-    /// The config authority will pay for rent exemption.
+    /// The NFT record account may NOT be initialized. To update an existing NFT record, close it
+    /// with [`GaiminInstruction::CloseNftRecord`] and create again. The NFT being registered must
+    /// be valid and of the programmable standard, and it must be created by the configured creator
+    /// unless the registrar signs as the payer. A registrar multisig is passed as the first
+    /// remaining account followed by its signers instead. The payer will pay for rent exemption.
+    /// Registration fails once the total amount of the record doesn't fit in what is left of
+    /// [`crate::state::Config::emission_budget`]. In pool mode, the record has no fixed allowance
+    /// and shares [`crate::state::Config::emission_rate`] while the NFT is staked.
     #[account(0, signer, name = "payer", desc = "Rent payer")]
     #[account(1, name = "mint", desc = "NFT mint account")]
     #[account(2, name = "metadata", desc = "NFT metadata account")]
     #[account(3, name = "edition", desc = "NFT edition account")]
     #[account(4, writable, name = "nft_record", desc = "NFT record PDA")]
-    #[account(5, name = "config", desc = "Config PDA")]
+    #[account(5, writable, name = "config", desc = "Config PDA")]
     #[account(6, name = "system_program", desc = "System program")]
     Nft,
 
@@ -67,16 +82,345 @@ pub enum GaiminInstruction {
 
     /// Instruction code: `0x4`
     ///
-    /// Add a reward for the given NFT to a provided claim record. It is a user instruction and
-    /// must be signed with the user's wallet account key. The user must own the NFT and the token
-    /// account must be locked. All accounts must be initialized.
+    /// Add a reward for the given NFT to a provided claim record. It is a user instruction and must
+    /// be signed with the user's wallet account key. Rewards only accrue while the NFT is staked by
+    /// the user with [`GaiminInstruction::Stake`], so the user must own the NFT and the token
+    /// account must be locked by the delegate PDA of the program. Rewards settled by
+    /// [`GaiminInstruction::Unstake`] are paid to the wallet that staked the NFT, whether or not it
+    /// still holds it, given its associated token account of the NFT. All accounts must be
+    /// initialized. Claiming is only possible between [`crate::state::Config::claimable_from`] and
+    /// [`crate::state::Config::claimable_until`], and rewards don't accrue after
+    /// [`crate::state::Config::accrual_ends_at`]. The reward is capped by what is left of
+    /// [`crate::state::Config::emission_budget`]. The epoch schedule account must be passed even if
+    /// the campaign has none.
     #[account(0, signer, name = "wallet", desc = "User wallet account/Rent payer")]
     #[account(1, name = "token", desc = "Token account")]
     #[account(2, name = "token_record", desc = "Token record account")]
     #[account(3, writable, name = "nft_record", desc = "NFT record PDA")]
     #[account(4, writable, name = "claim", desc = "Claim record PDA")]
-    #[account(5, name = "config", desc = "Config PDA")]
+    #[account(5, writable, name = "config", desc = "Config PDA")]
+    #[account(6, name = "epoch_schedule", desc = "Epoch schedule PDA")]
     Claim(ClaimArgs),
+
+    /// Instruction code: `0x5`
+    ///
+    /// Schedule a change of the config account. It is a system instruction that must be signed by
+    /// the config authority. The config account must be initialized first, and the pending config
+    /// change account may NOT be initialized. The change can be applied with
+    /// [`GaiminInstruction::ApplyConfigChange`] once [`crate::state::Config::timelock_duration`]
+    /// has passed. Only the fields present in the instruction data are changed, and the resulting
+    /// config is validated the same way as in [`GaiminInstruction::Config`]. The `payer` account
+    /// will pay for rent exemption.
+    #[account(0, signer, name = "authority", desc = "Config authority")]
+    #[account(1, signer, writable, name = "payer", desc = "Rent payer")]
+    #[account(2, name = "config", desc = "Config PDA")]
+    #[account(
+        3,
+        writable,
+        name = "config_change",
+        desc = "Pending config change PDA"
+    )]
+    #[account(4, name = "system_program", desc = "System program")]
+    ScheduleConfigChange(ConfigChangeArgs),
+
+    /// Instruction code: `0x6`
+    ///
+    /// Propose a new config authority. It is a system instruction that must be signed by the
+    /// config authority. The config account must be initialized first. The authority doesn't
+    /// change until the proposed account accepts it with [`GaiminInstruction::AcceptAuthority`].
+    /// A new proposal replaces the pending one.
+    #[account(0, signer, name = "authority", desc = "Config authority")]
+    #[account(1, name = "new_authority", desc = "Proposed config authority")]
+    #[account(2, writable, name = "config", desc = "Config PDA")]
+    ProposeAuthority,
+
+    /// Instruction code: `0x7`
+    ///
+    /// Accept the config authority proposed with [`GaiminInstruction::ProposeAuthority`]. It must
+    /// be signed by the proposed account. The config account must be initialized first.
+    #[account(0, signer, name = "new_authority", desc = "Proposed config authority")]
+    #[account(1, writable, name = "config", desc = "Config PDA")]
+    AcceptAuthority,
+
+    /// Instruction code: `0x8`
+    ///
+    /// Pause or resume NFT registration, claim record creation, claiming and staking. It is a
+    /// system instruction that must be signed by the config authority. The config account must be
+    /// initialized first. The given bit set replaces the current one.
+    #[account(0, signer, name = "authority", desc = "Config authority")]
+    #[account(1, writable, name = "config", desc = "Config PDA")]
+    SetPause(SetPauseArgs),
+
+    /// Instruction code: `0x9`
+    ///
+    /// Assign a role to an account, replacing its current holder. It is a system instruction that
+    /// must be signed by the config authority. The config account must be initialized first.
+    #[account(0, signer, name = "authority", desc = "Config authority")]
+    #[account(1, name = "account", desc = "New holder of the role")]
+    #[account(2, writable, name = "config", desc = "Config PDA")]
+    SetRole(SetRoleArgs),
+
+    /// Instruction code: `0xA`
+    ///
+    /// Apply a scheduled config change after its timelock has expired. It can be executed by
+    /// anyone. The config and pending config change accounts must be initialized. The change is
+    /// validated again against the current config, and the pending config change account is
+    /// deleted with its lamports returned to the account that paid for it. A change of the reward
+    /// rate checkpoints [`crate::state::Config::reward_index`] first, so it only applies to
+    /// rewards accrued after it.
+    #[account(0, writable, name = "config", desc = "Config PDA")]
+    #[account(
+        1,
+        writable,
+        name = "config_change",
+        desc = "Pending config change PDA"
+    )]
+    #[account(
+        2,
+        writable,
+        name = "receiver",
+        desc = "Rent payer of the config change"
+    )]
+    ApplyConfigChange,
+
+    /// Instruction code: `0xB`
+    ///
+    /// Cancel a scheduled config change. It is a system instruction that must be signed by the
+    /// config authority. The config and pending config change accounts must be initialized. The
+    /// pending config change account is deleted with its lamports returned to the account that
+    /// paid for it.
+    #[account(0, signer, name = "authority", desc = "Config authority")]
+    #[account(1, name = "config", desc = "Config PDA")]
+    #[account(
+        2,
+        writable,
+        name = "config_change",
+        desc = "Pending config change PDA"
+    )]
+    #[account(
+        3,
+        writable,
+        name = "receiver",
+        desc = "Rent payer of the config change"
+    )]
+    CancelConfigChange,
+
+    /// Instruction code: `0xC`
+    ///
+    /// Create or replace the multisig of a campaign. It is a system instruction that must be
+    /// signed by the config authority. The config account must be initialized first. The multisig
+    /// has no effect until it is made the config authority with
+    /// [`GaiminInstruction::ProposeAuthority`] or assigned a role with
+    /// [`GaiminInstruction::SetRole`]. The `payer` account will pay for rent exemption if the
+    /// multisig account doesn't exist yet.
+    #[account(0, signer, name = "authority", desc = "Config authority")]
+    #[account(1, signer, writable, name = "payer", desc = "Rent payer")]
+    #[account(2, writable, name = "multisig", desc = "Multisig PDA")]
+    #[account(3, name = "config", desc = "Config PDA")]
+    #[account(4, name = "system_program", desc = "System program")]
+    SetMultisig(SetMultisigArgs),
+
+    /// Instruction code: `0xD`
+    ///
+    /// Rewrite an account of the campaign stored in the version 0 layout to the current one, see
+    /// [`crate::state::Versioned`]. The account is resized in place. It is a system instruction
+    /// that must be signed by the config authority. The config account may be migrated with
+    /// this instruction itself, and it must be migrated before the NFT records of the campaign,
+    /// which are counted in [`crate::state::Config::nft_count`] and
+    /// [`crate::state::Config::allocated_reward`] once migrated. The `payer` account will pay for
+    /// the additional rent exemption.
+    #[account(0, signer, name = "authority", desc = "Config authority")]
+    #[account(1, signer, writable, name = "payer", desc = "Rent payer")]
+    #[account(2, writable, name = "target", desc = "Account to be migrated")]
+    #[account(3, writable, name = "config", desc = "Config PDA")]
+    #[account(4, name = "system_program", desc = "System program")]
+    Migrate,
+
+    /// Instruction code: `0xE`
+    ///
+    /// Close an NFT record account. It is a system instruction that must be signed by the config
+    /// authority. The lamports are returned to [`crate::state::NftRecord::payer`], or to any
+    /// receiver for records that don't store their payer. In pool mode, a staked NFT stops
+    /// sharing [`crate::state::Config::emission_rate`].
+    #[account(0, signer, name = "authority", desc = "Config authority")]
+    #[account(1, name = "mint", desc = "NFT mint account")]
+    #[account(2, writable, name = "nft_record", desc = "NFT record PDA")]
+    #[account(3, writable, name = "receiver", desc = "Rent payer of the NFT record")]
+    #[account(4, writable, name = "config", desc = "Config PDA")]
+    CloseNftRecord,
+
+    /// Instruction code: `0xF`
+    ///
+    /// Close a claim record account after the rewards have been sent. It is a system instruction
+    /// that must be signed by the settler or the config authority. The claim record must have
+    /// been settled with [`GaiminInstruction::SettleClaimRecord`] and its generation must have
+    /// ended. The lamports are returned to the owner of the claim record, who paid for rent
+    /// exemption.
+    #[account(0, signer, name = "authority", desc = "Settler/Config authority")]
+    #[account(1, writable, name = "claim", desc = "Claim record PDA")]
+    #[account(2, writable, name = "receiver", desc = "Owner of the claim record")]
+    #[account(3, name = "config", desc = "Config PDA")]
+    CloseClaimRecord,
+
+    /// Instruction code: `0x10`
+    ///
+    /// Create or replace the epoch schedule of a campaign, see
+    /// [`crate::state::EpochSchedule`]. It is a system instruction that must be signed by the
+    /// config authority. Epochs that start within [`crate::state::Config::timelock_duration`]
+    /// from now, including those that have started, can't be changed or removed, and new epochs
+    /// must start after it. The `payer` account will pay for rent exemption if the epoch schedule
+    /// account doesn't exist yet.
+    #[account(0, signer, name = "authority", desc = "Config authority")]
+    #[account(1, signer, writable, name = "payer", desc = "Rent payer")]
+    #[account(2, writable, name = "epoch_schedule", desc = "Epoch schedule PDA")]
+    #[account(3, name = "config", desc = "Config PDA")]
+    #[account(4, name = "system_program", desc = "System program")]
+    SetEpochSchedule(SetEpochScheduleArgs),
+
+    /// Instruction code: `0x11`
+    ///
+    /// Stake an NFT, so that rewards accrue for it from now on. It is a user instruction and must
+    /// be signed with the user's wallet account key, who must own the NFT. The NFT record must be
+    /// initialized. The delegate PDA of the program becomes the staking delegate of the token
+    /// and locks it through the Token Metadata program, so the NFT can't be transferred or
+    /// listed until it is unstaked from every campaign. A token it has locked already can be
+    /// staked in further campaigns, see [`crate::state::StakeLock`], which is created on the
+    /// first stake. Tokens locked by any other delegate must be unlocked first. The
+    /// authorization rules accounts are only needed for NFTs that have a rule set. An NFT staked
+    /// by another wallet can't be staked, since the rewards of the previous staker would be lost.
+    /// In pool mode, the NFT shares [`crate::state::Config::emission_rate`] until it is
+    /// unstaked.
+    #[account(
+        0,
+        signer,
+        writable,
+        name = "wallet",
+        desc = "User wallet account/Rent payer"
+    )]
+    #[account(1, writable, name = "token", desc = "Token account")]
+    #[account(2, writable, name = "token_record", desc = "Token record account")]
+    #[account(3, writable, name = "nft_record", desc = "NFT record PDA")]
+    #[account(4, writable, name = "config", desc = "Config PDA")]
+    #[account(5, name = "mint", desc = "NFT mint account")]
+    #[account(6, writable, name = "stake_lock", desc = "Stake lock PDA")]
+    #[account(7, writable, name = "metadata", desc = "NFT metadata account")]
+    #[account(8, name = "edition", desc = "NFT edition account")]
+    #[account(9, name = "delegate", desc = "Delegate PDA")]
+    #[account(10, name = "system_program", desc = "System program")]
+    #[account(11, name = "sysvar_instructions", desc = "Instructions sysvar")]
+    #[account(12, name = "spl_token_program", desc = "Token program")]
+    #[account(13, name = "token_metadata_program", desc = "Token Metadata program")]
+    #[account(
+        14,
+        optional,
+        name = "authorization_rules_program",
+        desc = "Token Authorization Rules program"
+    )]
+    #[account(
+        15,
+        optional,
+        name = "authorization_rules",
+        desc = "Token Authorization Rules account"
+    )]
+    Stake(StakeArgs),
+
+    /// Instruction code: `0x12`
+    ///
+    /// Unstake an NFT, so that rewards stop accruing for it. It must be signed by the wallet that
+    /// staked the NFT. Once the NFT isn't staked in any campaign, the token is unlocked, the
+    /// delegate PDA of the program is revoked and the stake lock PDA is closed, returning its rent
+    /// to the wallet. Tokens that weren't locked by the delegate PDA are left as they are. An NFT
+    /// whose record has been closed can still be unstaked this way. Rewards accrued until
+    /// unstaking are settled into [`crate::state::NftRecord::pending_reward`], which a later
+    /// [`GaiminInstruction::Claim`] pays unless another wallet stakes the NFT first. The epoch
+    /// schedule account must be passed even if the campaign has none. Unstaking can't be paused.
+    #[account(
+        0,
+        signer,
+        writable,
+        name = "wallet",
+        desc = "Staker wallet account/Rent payer"
+    )]
+    #[account(1, name = "mint", desc = "NFT mint account")]
+    #[account(2, writable, name = "nft_record", desc = "NFT record PDA")]
+    #[account(3, writable, name = "config", desc = "Config PDA")]
+    #[account(4, writable, name = "token", desc = "Token account")]
+    #[account(5, writable, name = "token_record", desc = "Token record account")]
+    #[account(6, writable, name = "stake_lock", desc = "Stake lock PDA")]
+    #[account(7, name = "epoch_schedule", desc = "Epoch schedule PDA")]
+    #[account(8, writable, name = "metadata", desc = "NFT metadata account")]
+    #[account(9, name = "edition", desc = "NFT edition account")]
+    #[account(10, name = "delegate", desc = "Delegate PDA")]
+    #[account(11, name = "system_program", desc = "System program")]
+    #[account(12, name = "sysvar_instructions", desc = "Instructions sysvar")]
+    #[account(13, name = "spl_token_program", desc = "Token program")]
+    #[account(14, name = "token_metadata_program", desc = "Token Metadata program")]
+    #[account(
+        15,
+        optional,
+        name = "authorization_rules_program",
+        desc = "Token Authorization Rules program"
+    )]
+    #[account(
+        16,
+        optional,
+        name = "authorization_rules",
+        desc = "Token Authorization Rules account"
+    )]
+    Unstake,
+
+    /// Instruction code: `0x13`
+    ///
+    /// Close the config account of a campaign. It is a system instruction that must be signed by
+    /// the config authority. The campaign must not have any NFT records left, see
+    /// [`GaiminInstruction::CloseNftRecord`], nor a pending config change or an epoch schedule,
+    /// see [`GaiminInstruction::CancelConfigChange`] and
+    /// [`GaiminInstruction::CloseEpochSchedule`]. The multisig of the campaign, which may be the
+    /// authority signing this instruction, is closed along with the config. The lamports are
+    /// returned to [`crate::state::Config::payer`]. Instruction code `0x1` was used by `Delete` in
+    /// version 0 of the program and is no longer accepted.
+    #[account(0, signer, name = "authority", desc = "Config authority")]
+    #[account(1, writable, name = "config", desc = "Config PDA")]
+    #[account(2, writable, name = "receiver", desc = "Rent payer of the config")]
+    #[account(3, name = "config_change", desc = "Pending config change PDA")]
+    #[account(4, name = "epoch_schedule", desc = "Epoch schedule PDA")]
+    #[account(5, writable, name = "multisig", desc = "Multisig PDA")]
+    CloseConfig,
+
+    /// Instruction code: `0x14`
+    ///
+    /// Close the multisig of a campaign. It is a system instruction that must be signed by the
+    /// config authority. The multisig must not be the config authority, the proposed authority or
+    /// a role holder. The lamports may be returned to any receiver.
+    #[account(0, signer, name = "authority", desc = "Config authority")]
+    #[account(1, writable, name = "multisig", desc = "Multisig PDA")]
+    #[account(2, writable, name = "receiver", desc = "Account to send lamports to")]
+    #[account(3, name = "config", desc = "Config PDA")]
+    CloseMultisig,
+
+    /// Instruction code: `0x15`
+    ///
+    /// Close the epoch schedule of a campaign, see [`crate::state::EpochSchedule`]. It is a
+    /// system instruction that must be signed by the config authority. Epochs that have started
+    /// were used to pay rewards, so the schedule can only be closed before its first epoch starts
+    /// or once the campaign has no NFT records left. The lamports may be returned to any
+    /// receiver.
+    #[account(0, signer, name = "authority", desc = "Config authority")]
+    #[account(1, writable, name = "epoch_schedule", desc = "Epoch schedule PDA")]
+    #[account(2, writable, name = "receiver", desc = "Account to send lamports to")]
+    #[account(3, name = "config", desc = "Config PDA")]
+    CloseEpochSchedule,
+
+    /// Instruction code: `0x16`
+    ///
+    /// Mark a claim record as settled once its reward has been sent, see
+    /// [`crate::state::ClaimRecord::settled`]. It is a system instruction that must be signed by
+    /// the settler or the config authority. A settled claim record can't receive more rewards
+    /// and can be closed with [`GaiminInstruction::CloseClaimRecord`].
+    #[account(0, signer, name = "authority", desc = "Settler/Config authority")]
+    #[account(1, writable, name = "claim", desc = "Claim record PDA")]
+    #[account(2, name = "config", desc = "Config PDA")]
+    SettleClaimRecord,
 }
 
 impl GaiminInstruction {
@@ -87,10 +431,27 @@ impl GaiminInstruction {
 
         Ok(match tag {
             0 => Self::Config(ConfigArgs::unpack_from_slice(rest)?),
-            1 => Self::Delete,
             2 => Self::Nft,
             3 => Self::CreateClaim(CreateClaimArgs::unpack_from_slice(rest)?),
             4 => Self::Claim(ClaimArgs::unpack_from_slice(rest)?),
+            5 => Self::ScheduleConfigChange(ConfigChangeArgs::unpack_from_slice(rest)?),
+            6 => Self::ProposeAuthority,
+            7 => Self::AcceptAuthority,
+            8 => Self::SetPause(SetPauseArgs::unpack_from_slice(rest)?),
+            9 => Self::SetRole(SetRoleArgs::unpack_from_slice(rest)?),
+            10 => Self::ApplyConfigChange,
+            11 => Self::CancelConfigChange,
+            12 => Self::SetMultisig(SetMultisigArgs::unpack_from_slice(rest)?),
+            13 => Self::Migrate,
+            14 => Self::CloseNftRecord,
+            15 => Self::CloseClaimRecord,
+            16 => Self::SetEpochSchedule(SetEpochScheduleArgs::unpack_from_slice(rest)?),
+            17 => Self::Stake(StakeArgs::unpack_from_slice(rest)?),
+            18 => Self::Unstake,
+            19 => Self::CloseConfig,
+            20 => Self::CloseMultisig,
+            21 => Self::CloseEpochSchedule,
+            22 => Self::SettleClaimRecord,
             i => {
                 msg!("[Error] Invalid instruction code: {}", i);
                 return Err(GaiminError::InvalidInstruction.into());
@@ -105,7 +466,6 @@ impl GaiminInstruction {
                 args.pack_into_slice(&mut res[1..]);
                 res
             }
-            Self::Delete => vec![1],
             Self::Nft => vec![2],
             Self::CreateClaim(args) => {
                 let mut res = vec![3; CreateClaimArgs::LEN + 1];
@@ -117,17 +477,68 @@ impl GaiminInstruction {
                 args.pack_into_slice(&mut res[1..]);
                 res
             }
+            Self::ScheduleConfigChange(args) => {
+                let mut res = vec![5; ConfigChangeArgs::LEN + 1];
+                args.pack_into_slice(&mut res[1..]);
+                res
+            }
+            Self::ProposeAuthority => vec![6],
+            Self::AcceptAuthority => vec![7],
+            Self::SetPause(args) => {
+                let mut res = vec![8; SetPauseArgs::LEN + 1];
+                args.pack_into_slice(&mut res[1..]);
+                res
+            }
+            Self::SetRole(args) => {
+                let mut res = vec![9; SetRoleArgs::LEN + 1];
+                args.pack_into_slice(&mut res[1..]);
+                res
+            }
+            Self::ApplyConfigChange => vec![10],
+            Self::CancelConfigChange => vec![11],
+            Self::SetMultisig(args) => {
+                let mut res = vec![12; SetMultisigArgs::LEN + 1];
+                args.pack_into_slice(&mut res[1..]);
+                res
+            }
+            Self::Migrate => vec![13],
+            Self::CloseNftRecord => vec![14],
+            Self::CloseClaimRecord => vec![15],
+            Self::SetEpochSchedule(args) => {
+                let mut res = vec![16; SetEpochScheduleArgs::LEN + 1];
+                args.pack_into_slice(&mut res[1..]);
+                res
+            }
+            Self::Stake(args) => {
+                let mut res = vec![17; StakeArgs::LEN + 1];
+                args.pack_into_slice(&mut res[1..]);
+                res
+            }
+            Self::Unstake => vec![18],
+            Self::CloseConfig => vec![19],
+            Self::CloseMultisig => vec![20],
+            Self::CloseEpochSchedule => vec![21],
+            Self::SettleClaimRecord => vec![22],
         }
     }
 }
 
 #[derive(Debug)]
 pub struct ConfigArgs {
-    pub claimable_from: i32,
-    pub accumulated_reward: i32,
-    pub initial_reward: i32,
-    pub total_accumulation_period: i32,
-    pub generation_duration: i32,
+    pub campaign_id: u64,
+    pub claimable_from: i64,
+    pub claimable_until: i64,
+    pub accrual_ends_at: i64,
+    pub accumulated_reward: u64,
+    pub initial_reward: u64,
+    pub total_accumulation_period: i64,
+    pub generation_duration: i64,
+    pub timelock_duration: i64,
+    pub reward_decimals: u8,
+    pub reward_curve: RewardCurve,
+    pub emission_budget: u64,
+    pub boost_schedule: BoostSchedule,
+    pub emission_rate: u64,
 }
 
 impl Sealed for ConfigArgs {}
@@ -138,45 +549,387 @@ impl IsInitialized for ConfigArgs {
 }
 
 impl Pack for ConfigArgs {
-    const LEN: usize = 5 * 4;
+    const LEN: usize = 8 + 8 * 8 + 1 + RewardCurve::LEN + 8 + BoostSchedule::LEN + 8;
 
     fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
         assert_ix_data_length(input, ConfigArgs::LEN)?;
         let src = array_ref![input, 0, ConfigArgs::LEN];
-        let (claimable_from, total_reward, initial_reward, reward_period_sec, generation_duration) = array_refs![
+        let (
+            campaign_id,
+            claimable_from,
+            claimable_until,
+            accrual_ends_at,
+            total_reward,
+            initial_reward,
+            reward_period_sec,
+            generation_duration,
+            timelock_duration,
+            reward_decimals,
+            reward_curve,
+            emission_budget,
+            boost_schedule,
+            emission_rate,
+        ) = array_refs![
             src,
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>()
+            mem::size_of::<u64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<u8>(),
+            RewardCurve::LEN,
+            mem::size_of::<u64>(),
+            BoostSchedule::LEN,
+            mem::size_of::<u64>()
         ];
 
         Ok(Self {
-            claimable_from: i32::from_le_bytes(*claimable_from),
-            accumulated_reward: i32::from_le_bytes(*total_reward),
-            initial_reward: i32::from_le_bytes(*initial_reward),
-            total_accumulation_period: i32::from_le_bytes(*reward_period_sec),
-            generation_duration: i32::from_le_bytes(*generation_duration),
+            campaign_id: u64::from_le_bytes(*campaign_id),
+            claimable_from: i64::from_le_bytes(*claimable_from),
+            claimable_until: i64::from_le_bytes(*claimable_until),
+            accrual_ends_at: i64::from_le_bytes(*accrual_ends_at),
+            accumulated_reward: u64::from_le_bytes(*total_reward),
+            initial_reward: u64::from_le_bytes(*initial_reward),
+            total_accumulation_period: i64::from_le_bytes(*reward_period_sec),
+            generation_duration: i64::from_le_bytes(*generation_duration),
+            timelock_duration: i64::from_le_bytes(*timelock_duration),
+            reward_decimals: reward_decimals[0],
+            reward_curve: RewardCurve::unpack_from_slice(reward_curve)?,
+            emission_budget: u64::from_le_bytes(*emission_budget),
+            boost_schedule: BoostSchedule::unpack_from_slice(boost_schedule)?,
+            emission_rate: u64::from_le_bytes(*emission_rate),
         })
     }
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let dst = array_mut_ref![dst, 0, ConfigArgs::LEN];
-        let (claimable_from, total_reward, initial_reward, reward_period_sec, generation_duration) = mut_array_refs![
+        let (
+            campaign_id,
+            claimable_from,
+            claimable_until,
+            accrual_ends_at,
+            total_reward,
+            initial_reward,
+            reward_period_sec,
+            generation_duration,
+            timelock_duration,
+            reward_decimals,
+            reward_curve,
+            emission_budget,
+            boost_schedule,
+            emission_rate,
+        ) = mut_array_refs![
             dst,
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>()
+            mem::size_of::<u64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<u8>(),
+            RewardCurve::LEN,
+            mem::size_of::<u64>(),
+            BoostSchedule::LEN,
+            mem::size_of::<u64>()
         ];
 
+        *campaign_id = self.campaign_id.to_le_bytes();
         *claimable_from = self.claimable_from.to_le_bytes();
+        *claimable_until = self.claimable_until.to_le_bytes();
+        *accrual_ends_at = self.accrual_ends_at.to_le_bytes();
         *total_reward = self.accumulated_reward.to_le_bytes();
         *initial_reward = self.initial_reward.to_le_bytes();
         *reward_period_sec = self.total_accumulation_period.to_le_bytes();
         *generation_duration = self.generation_duration.to_le_bytes();
+        *timelock_duration = self.timelock_duration.to_le_bytes();
+        reward_decimals[0] = self.reward_decimals;
+        self.reward_curve.pack_into_slice(reward_curve);
+        *emission_budget = self.emission_budget.to_le_bytes();
+        self.boost_schedule.pack_into_slice(boost_schedule);
+        *emission_rate = self.emission_rate.to_le_bytes();
+    }
+}
+
+/// Every field is encoded as a presence flag (`0` or `1`) followed by the value, which is ignored
+/// when the flag is not set. Setting `accrual_ends_at` to zero removes the accrual end date
+#[derive(Debug, Default)]
+pub struct ConfigChangeArgs {
+    pub creator: Option<Pubkey>,
+    pub claimable_from: Option<i64>,
+    pub claimable_until: Option<i64>,
+    pub accrual_ends_at: Option<i64>,
+    pub accumulated_reward: Option<u64>,
+    pub initial_reward: Option<u64>,
+    pub accumulation_duration: Option<i64>,
+    pub generation_duration: Option<i64>,
+    pub timelock_duration: Option<i64>,
+    pub reward_curve: Option<RewardCurve>,
+    pub emission_budget: Option<u64>,
+    pub boost_schedule: Option<BoostSchedule>,
+    pub emission_rate: Option<u64>,
+}
+
+impl Sealed for ConfigChangeArgs {}
+impl IsInitialized for ConfigChangeArgs {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+
+impl Pack for ConfigChangeArgs {
+    const LEN: usize = (1 + 32)
+        + 8 * (1 + 8)
+        + (1 + RewardCurve::LEN)
+        + (1 + 8)
+        + (1 + BoostSchedule::LEN)
+        + (1 + 8);
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        assert_ix_data_length(input, ConfigChangeArgs::LEN)?;
+        let src = array_ref![input, 0, ConfigChangeArgs::LEN];
+        let (
+            creator_flag,
+            creator,
+            claimable_from_flag,
+            claimable_from,
+            claimable_until_flag,
+            claimable_until,
+            accrual_ends_at_flag,
+            accrual_ends_at,
+            accumulated_reward_flag,
+            accumulated_reward,
+            initial_reward_flag,
+            initial_reward,
+            accumulation_duration_flag,
+            accumulation_duration,
+            generation_duration_flag,
+            generation_duration,
+            timelock_duration_flag,
+            timelock_duration,
+            reward_curve_flag,
+            reward_curve,
+            emission_budget_flag,
+            emission_budget,
+            boost_schedule_flag,
+            boost_schedule,
+            emission_rate_flag,
+            emission_rate,
+        ) = array_refs![
+            src,
+            1,
+            mem::size_of::<Pubkey>(),
+            1,
+            mem::size_of::<i64>(),
+            1,
+            mem::size_of::<i64>(),
+            1,
+            mem::size_of::<i64>(),
+            1,
+            mem::size_of::<u64>(),
+            1,
+            mem::size_of::<u64>(),
+            1,
+            mem::size_of::<i64>(),
+            1,
+            mem::size_of::<i64>(),
+            1,
+            mem::size_of::<i64>(),
+            1,
+            RewardCurve::LEN,
+            1,
+            mem::size_of::<u64>(),
+            1,
+            BoostSchedule::LEN,
+            1,
+            mem::size_of::<u64>()
+        ];
+
+        Ok(Self {
+            creator: unpack_option(creator_flag[0], Pubkey::from(*creator))?,
+            claimable_from: unpack_option(
+                claimable_from_flag[0],
+                i64::from_le_bytes(*claimable_from),
+            )?,
+            claimable_until: unpack_option(
+                claimable_until_flag[0],
+                i64::from_le_bytes(*claimable_until),
+            )?,
+            accrual_ends_at: unpack_option(
+                accrual_ends_at_flag[0],
+                i64::from_le_bytes(*accrual_ends_at),
+            )?,
+            accumulated_reward: unpack_option(
+                accumulated_reward_flag[0],
+                u64::from_le_bytes(*accumulated_reward),
+            )?,
+            initial_reward: unpack_option(
+                initial_reward_flag[0],
+                u64::from_le_bytes(*initial_reward),
+            )?,
+            accumulation_duration: unpack_option(
+                accumulation_duration_flag[0],
+                i64::from_le_bytes(*accumulation_duration),
+            )?,
+            generation_duration: unpack_option(
+                generation_duration_flag[0],
+                i64::from_le_bytes(*generation_duration),
+            )?,
+            timelock_duration: unpack_option(
+                timelock_duration_flag[0],
+                i64::from_le_bytes(*timelock_duration),
+            )?,
+            reward_curve: unpack_option(reward_curve_flag[0], reward_curve)?
+                .map(|curve| RewardCurve::unpack_from_slice(curve))
+                .transpose()?,
+            emission_budget: unpack_option(
+                emission_budget_flag[0],
+                u64::from_le_bytes(*emission_budget),
+            )?,
+            boost_schedule: unpack_option(boost_schedule_flag[0], boost_schedule)?
+                .map(|schedule| BoostSchedule::unpack_from_slice(schedule))
+                .transpose()?,
+            emission_rate: unpack_option(
+                emission_rate_flag[0],
+                u64::from_le_bytes(*emission_rate),
+            )?,
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, ConfigChangeArgs::LEN];
+        let (
+            creator_flag,
+            creator,
+            claimable_from_flag,
+            claimable_from,
+            claimable_until_flag,
+            claimable_until,
+            accrual_ends_at_flag,
+            accrual_ends_at,
+            accumulated_reward_flag,
+            accumulated_reward,
+            initial_reward_flag,
+            initial_reward,
+            accumulation_duration_flag,
+            accumulation_duration,
+            generation_duration_flag,
+            generation_duration,
+            timelock_duration_flag,
+            timelock_duration,
+            reward_curve_flag,
+            reward_curve,
+            emission_budget_flag,
+            emission_budget,
+            boost_schedule_flag,
+            boost_schedule,
+            emission_rate_flag,
+            emission_rate,
+        ) = mut_array_refs![
+            dst,
+            1,
+            mem::size_of::<Pubkey>(),
+            1,
+            mem::size_of::<i64>(),
+            1,
+            mem::size_of::<i64>(),
+            1,
+            mem::size_of::<i64>(),
+            1,
+            mem::size_of::<u64>(),
+            1,
+            mem::size_of::<u64>(),
+            1,
+            mem::size_of::<i64>(),
+            1,
+            mem::size_of::<i64>(),
+            1,
+            mem::size_of::<i64>(),
+            1,
+            RewardCurve::LEN,
+            1,
+            mem::size_of::<u64>(),
+            1,
+            BoostSchedule::LEN,
+            1,
+            mem::size_of::<u64>()
+        ];
+
+        pack_option(self.creator.map(|x| x.to_bytes()), creator_flag, creator);
+        pack_option(
+            self.claimable_from.map(i64::to_le_bytes),
+            claimable_from_flag,
+            claimable_from,
+        );
+        pack_option(
+            self.claimable_until.map(i64::to_le_bytes),
+            claimable_until_flag,
+            claimable_until,
+        );
+        pack_option(
+            self.accrual_ends_at.map(i64::to_le_bytes),
+            accrual_ends_at_flag,
+            accrual_ends_at,
+        );
+        pack_option(
+            self.accumulated_reward.map(u64::to_le_bytes),
+            accumulated_reward_flag,
+            accumulated_reward,
+        );
+        pack_option(
+            self.initial_reward.map(u64::to_le_bytes),
+            initial_reward_flag,
+            initial_reward,
+        );
+        pack_option(
+            self.accumulation_duration.map(i64::to_le_bytes),
+            accumulation_duration_flag,
+            accumulation_duration,
+        );
+        pack_option(
+            self.generation_duration.map(i64::to_le_bytes),
+            generation_duration_flag,
+            generation_duration,
+        );
+        pack_option(
+            self.timelock_duration.map(i64::to_le_bytes),
+            timelock_duration_flag,
+            timelock_duration,
+        );
+        pack_option(
+            self.reward_curve.as_ref().map(|curve| {
+                let mut packed = [0; RewardCurve::LEN];
+                curve.pack_into_slice(&mut packed);
+                packed
+            }),
+            reward_curve_flag,
+            reward_curve,
+        );
+        pack_option(
+            self.emission_budget.map(u64::to_le_bytes),
+            emission_budget_flag,
+            emission_budget,
+        );
+        pack_option(
+            self.boost_schedule.as_ref().map(|schedule| {
+                let mut packed = [0; BoostSchedule::LEN];
+                schedule.pack_into_slice(&mut packed);
+                packed
+            }),
+            boost_schedule_flag,
+            boost_schedule,
+        );
+        pack_option(
+            self.emission_rate.map(u64::to_le_bytes),
+            emission_rate_flag,
+            emission_rate,
+        );
     }
 }
 
@@ -254,3 +1007,227 @@ impl Pack for ClaimArgs {
         dst[2] = self.nft_record_bump;
     }
 }
+
+#[derive(Debug)]
+pub struct StakeArgs {
+    pub token_acc_bump: u8,
+    pub token_record_bump: u8,
+    pub nft_record_bump: u8,
+}
+
+impl Sealed for StakeArgs {}
+impl IsInitialized for StakeArgs {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+
+impl Pack for StakeArgs {
+    const LEN: usize = 3;
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        assert_ix_data_length(input, StakeArgs::LEN)?;
+
+        Ok(StakeArgs {
+            token_acc_bump: input[0],
+            token_record_bump: input[1],
+            nft_record_bump: input[2],
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[0] = self.token_acc_bump;
+        dst[1] = self.token_record_bump;
+        dst[2] = self.nft_record_bump;
+    }
+}
+
+#[derive(Debug)]
+pub struct SetPauseArgs {
+    /// Bit set of paused operations, see [`crate::state::PAUSE_ALL`]
+    pub paused: u8,
+}
+
+impl Sealed for SetPauseArgs {}
+impl IsInitialized for SetPauseArgs {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+
+impl Pack for SetPauseArgs {
+    const LEN: usize = 1;
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        assert_ix_data_length(input, SetPauseArgs::LEN)?;
+
+        Ok(SetPauseArgs { paused: input[0] })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[0] = self.paused;
+    }
+}
+
+#[derive(Debug)]
+pub struct SetRoleArgs {
+    pub role: Role,
+}
+
+impl Sealed for SetRoleArgs {}
+impl IsInitialized for SetRoleArgs {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+
+impl Pack for SetRoleArgs {
+    const LEN: usize = 1;
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        assert_ix_data_length(input, SetRoleArgs::LEN)?;
+
+        let role = match input[0] {
+            0 => Role::Registrar,
+            1 => Role::Settler,
+            i => {
+                msg!("[Error] Invalid role: {}", i);
+                return Err(ProgramError::InvalidInstructionData);
+            }
+        };
+
+        Ok(SetRoleArgs { role })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[0] = self.role as u8;
+    }
+}
+
+/// Signers are encoded as a count followed by [`MAX_MULTISIG_SIGNERS`] slots, unused slots are
+/// zeroed
+#[derive(Debug)]
+pub struct SetMultisigArgs {
+    /// Number of signers required to authorize an instruction
+    pub threshold: u8,
+
+    /// Accounts allowed to sign on behalf of the multisig
+    pub signers: Vec<Pubkey>,
+}
+
+impl Sealed for SetMultisigArgs {}
+impl IsInitialized for SetMultisigArgs {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+
+impl Pack for SetMultisigArgs {
+    const LEN: usize = 1 + 1 + MAX_MULTISIG_SIGNERS * 32;
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        assert_ix_data_length(input, SetMultisigArgs::LEN)?;
+        let src = array_ref![input, 0, SetMultisigArgs::LEN];
+        let (threshold, signer_count, signers) = array_refs![
+            src,
+            mem::size_of::<u8>(),
+            mem::size_of::<u8>(),
+            MAX_MULTISIG_SIGNERS * 32
+        ];
+
+        let signer_count = signer_count[0] as usize;
+        if signer_count > MAX_MULTISIG_SIGNERS {
+            msg!("[Error] Too many multisig signers: {}", signer_count);
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(SetMultisigArgs {
+            threshold: threshold[0],
+            signers: signers
+                .chunks_exact(32)
+                .take(signer_count)
+                .map(|signer| Pubkey::try_from(signer).unwrap())
+                .collect(),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, SetMultisigArgs::LEN];
+        let (threshold, signer_count, signers) = mut_array_refs![
+            dst,
+            mem::size_of::<u8>(),
+            mem::size_of::<u8>(),
+            MAX_MULTISIG_SIGNERS * 32
+        ];
+
+        threshold[0] = self.threshold;
+        signer_count[0] = self.signers.len() as u8;
+        signers.fill(0);
+        for (dst, signer) in signers.chunks_exact_mut(32).zip(&self.signers) {
+            dst.copy_from_slice(&signer.to_bytes());
+        }
+    }
+}
+
+/// Epochs are encoded as their count followed by [`MAX_EPOCHS`] slots, unused slots are zeroed
+#[derive(Debug)]
+pub struct SetEpochScheduleArgs {
+    /// Epochs ordered by their start
+    pub epochs: Vec<Epoch>,
+}
+
+impl Sealed for SetEpochScheduleArgs {}
+impl IsInitialized for SetEpochScheduleArgs {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+
+impl Pack for SetEpochScheduleArgs {
+    const LEN: usize = 1 + MAX_EPOCHS * (8 + 4 + 8);
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        assert_ix_data_length(input, SetEpochScheduleArgs::LEN)?;
+        let src = array_ref![input, 0, SetEpochScheduleArgs::LEN];
+        let (epoch_count, epochs) =
+            array_refs![src, mem::size_of::<u8>(), MAX_EPOCHS * (8 + 4 + 8)];
+
+        let epoch_count = epoch_count[0] as usize;
+        if epoch_count > MAX_EPOCHS {
+            msg!("[Error] Too many epochs: {}", epoch_count);
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(SetEpochScheduleArgs {
+            epochs: epochs
+                .chunks_exact(8 + 4 + 8)
+                .take(epoch_count)
+                .map(|epoch| {
+                    let (starts_at, rate_bps, initial_bonus) =
+                        array_refs![array_ref![epoch, 0, 8 + 4 + 8], 8, 4, 8];
+                    Epoch {
+                        starts_at: i64::from_le_bytes(*starts_at),
+                        rate_bps: u32::from_le_bytes(*rate_bps),
+                        initial_bonus: u64::from_le_bytes(*initial_bonus),
+                    }
+                })
+                .collect(),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, SetEpochScheduleArgs::LEN];
+        let (epoch_count, epochs) =
+            mut_array_refs![dst, mem::size_of::<u8>(), MAX_EPOCHS * (8 + 4 + 8)];
+
+        epoch_count[0] = self.epochs.len() as u8;
+        epochs.fill(0);
+        for (dst, epoch) in epochs.chunks_exact_mut(8 + 4 + 8).zip(&self.epochs) {
+            let (starts_at, rate_bps, initial_bonus) =
+                mut_array_refs![array_mut_ref![dst, 0, 8 + 4 + 8], 8, 4, 8];
+            *starts_at = epoch.starts_at.to_le_bytes();
+            *rate_bps = epoch.rate_bps.to_le_bytes();
+            *initial_bonus = epoch.initial_bonus.to_le_bytes();
+        }
+    }
+}
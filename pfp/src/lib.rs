@@ -2,6 +2,7 @@ pub mod entrypoint;
 pub mod error;
 pub mod instruction;
 pub mod processor;
+pub mod reward;
 pub mod state;
 pub mod utils;
 
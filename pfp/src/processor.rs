@@ -1,26 +1,45 @@
 use mpl_token_metadata::{
     accounts::{Metadata, TokenRecord},
-    types::{TokenStandard, TokenState},
+    instructions::{
+        DelegateStakingV1Cpi, DelegateStakingV1CpiAccounts, DelegateStakingV1InstructionArgs,
+        LockV1Cpi, LockV1CpiAccounts, LockV1InstructionArgs, RevokeStakingV1Cpi,
+        RevokeStakingV1CpiAccounts, UnlockV1Cpi, UnlockV1CpiAccounts, UnlockV1InstructionArgs,
+    },
+    types::{TokenDelegateRole, TokenStandard, TokenState},
 };
 
 use solana_program::{
     account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, msg,
-    program::invoke_signed, program_error::ProgramError, program_pack::Pack, pubkey,
-    pubkey::Pubkey, sysvar::Sysvar,
+    program_error::ProgramError, program_pack::Pack, pubkey, pubkey::Pubkey, sysvar::Sysvar,
 };
 
 use spl_token::state::{Account, Mint};
 
 use crate::{
     error::GaiminError,
-    instruction::{accounts::*, ClaimArgs, ConfigArgs, CreateClaimArgs, GaiminInstruction},
-    state::{ClaimRecord, Config, NftRecord},
+    instruction::{
+        accounts::*, ClaimArgs, ConfigArgs, ConfigChangeArgs, CreateClaimArgs, GaiminInstruction,
+        SetEpochScheduleArgs, SetMultisigArgs, SetPauseArgs, SetRoleArgs, StakeArgs,
+    },
+    reward,
+    state::{
+        AccountType, ClaimRecord, Config, Epoch, EpochSchedule, Multisig, NftRecord,
+        PendingConfigChange, RewardCurve, Role, StakeLock, Versioned, MAX_BOOST_TIERS,
+        MAX_REWARD_TIERS, MAX_STAKED_CAMPAIGNS, PAUSE_ALL, PAUSE_CLAIM, PAUSE_CREATE_CLAIM,
+        PAUSE_REGISTRATION, PAUSE_STAKE,
+    },
     utils::*,
 };
 
 pub const CONFIG_PDA_SEED: &[u8] = b"config";
 pub const NFT_PDA_SEED: &[u8] = b"nft";
 pub const CLAIM_PDA_SEED: &[u8] = b"claim";
+pub const CONFIG_CHANGE_PDA_SEED: &[u8] = b"config_change";
+pub const MULTISIG_PDA_SEED: &[u8] = b"multisig";
+pub const EPOCH_SCHEDULE_PDA_SEED: &[u8] = b"epoch_schedule";
+pub const DELEGATE_PDA_SEED: &[u8] = b"delegate";
+pub const STAKE_LOCK_PDA_SEED: &[u8] = b"stake_lock";
+pub const DEFAULT_CAMPAIGN_ID: u64 = 0;
 pub const MPL_TOKEN_METADATA_PROGRAM_ID: Pubkey =
     pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
 pub const SPL_ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID: Pubkey =
@@ -43,11 +62,9 @@ impl Processor {
                 ConfigAccounts::context(accounts)?.accounts,
                 data,
             ),
-            GaiminInstruction::Delete => {
-                Self::process_delete(program_id, DeleteAccounts::context(accounts)?.accounts)
-            }
             GaiminInstruction::Nft => {
-                Self::process_nft(program_id, NftAccounts::context(accounts)?.accounts)
+                let ctx = NftAccounts::context(accounts)?;
+                Self::process_nft(program_id, ctx.accounts, ctx.remaining_accounts)
             }
             GaiminInstruction::CreateClaim(data) => Self::process_create_claim(
                 program_id,
@@ -57,6 +74,86 @@ impl Processor {
             GaiminInstruction::Claim(data) => {
                 Self::process_claim(program_id, ClaimAccounts::context(accounts)?.accounts, data)
             }
+            GaiminInstruction::ScheduleConfigChange(data) => {
+                let ctx = ScheduleConfigChangeAccounts::context(accounts)?;
+                Self::process_schedule_config_change(
+                    program_id,
+                    ctx.accounts,
+                    ctx.remaining_accounts,
+                    data,
+                )
+            }
+            GaiminInstruction::ProposeAuthority => {
+                let ctx = ProposeAuthorityAccounts::context(accounts)?;
+                Self::process_propose_authority(program_id, ctx.accounts, ctx.remaining_accounts)
+            }
+            GaiminInstruction::AcceptAuthority => {
+                let ctx = AcceptAuthorityAccounts::context(accounts)?;
+                Self::process_accept_authority(program_id, ctx.accounts, ctx.remaining_accounts)
+            }
+            GaiminInstruction::SetPause(data) => {
+                let ctx = SetPauseAccounts::context(accounts)?;
+                Self::process_set_pause(program_id, ctx.accounts, ctx.remaining_accounts, data)
+            }
+            GaiminInstruction::SetRole(data) => {
+                let ctx = SetRoleAccounts::context(accounts)?;
+                Self::process_set_role(program_id, ctx.accounts, ctx.remaining_accounts, data)
+            }
+            GaiminInstruction::ApplyConfigChange => Self::process_apply_config_change(
+                program_id,
+                ApplyConfigChangeAccounts::context(accounts)?.accounts,
+            ),
+            GaiminInstruction::CancelConfigChange => {
+                let ctx = CancelConfigChangeAccounts::context(accounts)?;
+                Self::process_cancel_config_change(program_id, ctx.accounts, ctx.remaining_accounts)
+            }
+            GaiminInstruction::Migrate => {
+                let ctx = MigrateAccounts::context(accounts)?;
+                Self::process_migrate(program_id, ctx.accounts, ctx.remaining_accounts)
+            }
+            GaiminInstruction::SetMultisig(data) => {
+                let ctx = SetMultisigAccounts::context(accounts)?;
+                Self::process_set_multisig(program_id, ctx.accounts, ctx.remaining_accounts, data)
+            }
+            GaiminInstruction::CloseNftRecord => {
+                let ctx = CloseNftRecordAccounts::context(accounts)?;
+                Self::process_close_nft_record(program_id, ctx.accounts, ctx.remaining_accounts)
+            }
+            GaiminInstruction::CloseClaimRecord => {
+                let ctx = CloseClaimRecordAccounts::context(accounts)?;
+                Self::process_close_claim_record(program_id, ctx.accounts, ctx.remaining_accounts)
+            }
+            GaiminInstruction::SetEpochSchedule(data) => {
+                let ctx = SetEpochScheduleAccounts::context(accounts)?;
+                Self::process_set_epoch_schedule(
+                    program_id,
+                    ctx.accounts,
+                    ctx.remaining_accounts,
+                    data,
+                )
+            }
+            GaiminInstruction::Stake(data) => {
+                Self::process_stake(program_id, StakeAccounts::context(accounts)?.accounts, data)
+            }
+            GaiminInstruction::Unstake => {
+                Self::process_unstake(program_id, UnstakeAccounts::context(accounts)?.accounts)
+            }
+            GaiminInstruction::CloseConfig => {
+                let ctx = CloseConfigAccounts::context(accounts)?;
+                Self::process_close_config(program_id, ctx.accounts, ctx.remaining_accounts)
+            }
+            GaiminInstruction::CloseMultisig => {
+                let ctx = CloseMultisigAccounts::context(accounts)?;
+                Self::process_close_multisig(program_id, ctx.accounts, ctx.remaining_accounts)
+            }
+            GaiminInstruction::CloseEpochSchedule => {
+                let ctx = CloseEpochScheduleAccounts::context(accounts)?;
+                Self::process_close_epoch_schedule(program_id, ctx.accounts, ctx.remaining_accounts)
+            }
+            GaiminInstruction::SettleClaimRecord => {
+                let ctx = SettleClaimRecordAccounts::context(accounts)?;
+                Self::process_settle_claim_record(program_id, ctx.accounts, ctx.remaining_accounts)
+            }
         }
     }
 
@@ -67,70 +164,746 @@ impl Processor {
     ) -> ProgramResult {
         // Authority validation
         assert_signer(accounts.authority)?;
+        assert_upgrade_authority(accounts.program_data, program_id, accounts.authority.key)?;
 
         // Config validation
-        let bump = assert_derived_from(accounts.config, program_id, &[CONFIG_PDA_SEED])?;
-        assert_uninitialized(accounts.config)?;
+        let campaign_id = data.campaign_id.to_le_bytes();
+        let bump = assert_derived_from(
+            accounts.config,
+            program_id,
+            &[CONFIG_PDA_SEED, campaign_seed(&campaign_id)],
+        )?;
+        assert_uninitialized(accounts.config, program_id)?;
 
-        let accumulation_duration = data.total_accumulation_period / data.accumulated_reward;
+        let config = Config {
+            campaign_id: data.campaign_id,
+            authority: *accounts.authority.key,
+            pending_authority: Pubkey::default(),
+            creator: *accounts.creator.key,
+            registrar: *accounts.authority.key,
+            settler: *accounts.authority.key,
+            claimable_from: data.claimable_from,
+            claimable_until: data.claimable_until,
+            accrual_ends_at: data.accrual_ends_at,
+            accumulated_reward: data.accumulated_reward,
+            initial_reward: data.initial_reward,
+            accumulation_duration: i64::try_from(data.accumulated_reward)
+                .ok()
+                .and_then(|reward| data.total_accumulation_period.checked_div(reward))
+                .unwrap_or_default(),
+            generation_duration: data.generation_duration,
+            timelock_duration: data.timelock_duration,
+            reward_decimals: data.reward_decimals,
+            paused: 0,
+            nft_count: 0,
+            payer: *accounts.authority.key,
+            reward_curve: data.reward_curve,
+            emission_budget: data.emission_budget,
+            allocated_reward: 0,
+            claimed_reward: 0,
+            boost_schedule: data.boost_schedule,
+            reward_index: 0,
+            index_updated_at: 0,
+            emission_rate: data.emission_rate,
+            staked_count: 0,
+            index_history: vec![],
+        };
+        validate_config(&config)?;
 
-        if data.accumulated_reward < 0
-            || data.initial_reward < 0
-            || accumulation_duration <= 0
-            || data.generation_duration < 0
-        {
-            msg!("[Error] Config data is invalid");
-            return Err(GaiminError::InvalidConfig.into());
+        // Config creation
+        create_pda_account::<Config>(
+            accounts.config,
+            accounts.authority,
+            program_id,
+            &[CONFIG_PDA_SEED, campaign_seed(&campaign_id), &[bump]],
+        )?;
+
+        Config::pack(config, &mut accounts.config.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_schedule_config_change(
+        program_id: &Pubkey,
+        accounts: ScheduleConfigChangeAccounts,
+        signers: &[AccountInfo],
+        data: ConfigChangeArgs,
+    ) -> ProgramResult {
+        // Config validation
+        let mut config = load_config(accounts.config, program_id)?;
+        let campaign_id = config.campaign_id.to_le_bytes();
+
+        // Authority validation
+        assert_authority(program_id, &config.authority, accounts.authority, signers)?;
+
+        // Change validation against the current config
+        let now = Clock::get()?.unix_timestamp;
+        validate_config_change(&config, &data)?;
+        if changes_rate(&data) {
+            reward::checkpoint(&mut config, now)?;
         }
+        apply_config_change(&mut config, &data);
+        validate_config(&config)?;
 
-        data.initial_reward
-            .checked_add(data.accumulated_reward)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
+        // Pending config change validation
+        let bump = assert_derived_from(
+            accounts.config_change,
+            program_id,
+            &[CONFIG_CHANGE_PDA_SEED, campaign_seed(&campaign_id)],
+        )?;
+        assert_uninitialized(accounts.config_change, program_id)?;
 
-        // Config creation
-        invoke_signed(
-            &create_account_ix::<Config>(accounts.config.key, accounts.authority.key, program_id),
-            &[accounts.authority.clone(), accounts.config.clone()],
-            &[&[CONFIG_PDA_SEED, &[bump]]],
-        )?;
-
-        Config::pack(
-            Config {
-                authority: *accounts.authority.key,
-                creator: *accounts.creator.key,
-                claimable_from: data.claimable_from,
-                accumulated_reward: data.accumulated_reward,
-                initial_reward: data.initial_reward,
-                accumulation_duration,
-                generation_duration: data.generation_duration,
+        // Pending config change creation
+        assert_signer(accounts.payer)?;
+        create_pda_account::<PendingConfigChange>(
+            accounts.config_change,
+            accounts.payer,
+            program_id,
+            &[CONFIG_CHANGE_PDA_SEED, campaign_seed(&campaign_id), &[bump]],
+        )?;
+
+        PendingConfigChange::pack(
+            PendingConfigChange {
+                payer: *accounts.payer.key,
+                effective_at: now
+                    .checked_add(config.timelock_duration)
+                    .ok_or(ProgramError::ArithmeticOverflow)?,
+                change: data,
             },
-            &mut accounts.config.try_borrow_mut_data()?,
+            &mut accounts.config_change.try_borrow_mut_data()?,
         )?;
 
         Ok(())
     }
 
-    fn process_delete(program_id: &Pubkey, accounts: DeleteAccounts) -> ProgramResult {
+    fn process_apply_config_change(
+        program_id: &Pubkey,
+        accounts: ApplyConfigChangeAccounts,
+    ) -> ProgramResult {
+        // Config validation
+        let mut config = load_config(accounts.config, program_id)?;
+
+        // Pending config change validation
+        let pending = load_config_change(accounts.config_change, &config, program_id)?;
+        let now = Clock::get()?.unix_timestamp;
+        if now < pending.effective_at {
+            msg!(
+                "[Error] Config change cannot be applied before {}",
+                pending.effective_at
+            );
+            return Err(GaiminError::TimelockNotExpired.into());
+        }
+
+        if pending.payer != *accounts.receiver.key {
+            msg!("[Error] Lamports must be returned to {}", pending.payer);
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // Config update. Rewards accrued so far keep the rate they accrued at
+        let change = &pending.change;
+        validate_config_change(&config, change)?;
+        if changes_rate(change) {
+            reward::checkpoint(&mut config, now)?;
+        }
+        apply_config_change(&mut config, change);
+        validate_config(&config)?;
+        config.save()?;
+
+        close_account(accounts.config_change, accounts.receiver)
+    }
+
+    fn process_cancel_config_change(
+        program_id: &Pubkey,
+        accounts: CancelConfigChangeAccounts,
+        signers: &[AccountInfo],
+    ) -> ProgramResult {
         // Config validation
-        assert_derived_from(accounts.config, program_id, &[CONFIG_PDA_SEED])?;
-        assert_initialized(accounts.config)?;
+        let config = load_config(accounts.config, program_id)?;
 
         // Authority validation
-        assert_signer(accounts.authority)?;
-        let config = Config::unpack_unchecked(&accounts.config.try_borrow_data()?)?;
-        if config.authority != *accounts.authority.key {
+        assert_authority(program_id, &config.authority, accounts.authority, signers)?;
+
+        // Pending config change validation
+        let pending = load_config_change(accounts.config_change, &config, program_id)?;
+        if pending.payer != *accounts.receiver.key {
+            msg!("[Error] Lamports must be returned to {}", pending.payer);
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        close_account(accounts.config_change, accounts.receiver)
+    }
+
+    fn process_propose_authority(
+        program_id: &Pubkey,
+        accounts: ProposeAuthorityAccounts,
+        signers: &[AccountInfo],
+    ) -> ProgramResult {
+        // Config validation
+        let mut config = load_config(accounts.config, program_id)?;
+
+        // Authority validation
+        assert_authority(program_id, &config.authority, accounts.authority, signers)?;
+
+        // Config update
+        config.pending_authority = *accounts.new_authority.key;
+        config.save()
+    }
+
+    fn process_accept_authority(
+        program_id: &Pubkey,
+        accounts: AcceptAuthorityAccounts,
+        signers: &[AccountInfo],
+    ) -> ProgramResult {
+        // Config validation
+        let mut config = load_config(accounts.config, program_id)?;
+
+        // New authority validation
+        if config.pending_authority == Pubkey::default() {
+            msg!("[Error] There is no pending config authority");
             return Err(GaiminError::PermissionDenied.into());
         }
+        assert_authority(
+            program_id,
+            &config.pending_authority,
+            accounts.new_authority,
+            signers,
+        )?;
+
+        // Config update
+        config.authority = config.pending_authority;
+        config.pending_authority = Pubkey::default();
+        config.save()
+    }
+
+    fn process_set_pause(
+        program_id: &Pubkey,
+        accounts: SetPauseAccounts,
+        signers: &[AccountInfo],
+        data: SetPauseArgs,
+    ) -> ProgramResult {
+        // Config validation
+        let mut config = load_config(accounts.config, program_id)?;
+
+        // Authority validation
+        assert_authority(program_id, &config.authority, accounts.authority, signers)?;
+
+        if data.paused & !PAUSE_ALL != 0 {
+            msg!("[Error] Unknown pause flags: {:#b}", data.paused);
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        // Config update
+        config.paused = data.paused;
+        config.save()
+    }
+
+    fn process_close_config(
+        program_id: &Pubkey,
+        accounts: CloseConfigAccounts,
+        signers: &[AccountInfo],
+    ) -> ProgramResult {
+        // Config validation
+        let config = load_config(accounts.config, program_id)?;
+        let campaign_id = config.campaign_id.to_le_bytes();
+
+        // Authority validation
+        assert_authority(program_id, &config.authority, accounts.authority, signers)?;
+
+        if config.nft_count > 0 {
+            msg!(
+                "[Error] Campaign still has {} NFT records",
+                config.nft_count
+            );
+            return Err(GaiminError::AccountInUse.into());
+        }
+
+        // Accounts of the campaign left open would apply to a config created again for it
+        assert_derived_from(
+            accounts.config_change,
+            program_id,
+            &[CONFIG_CHANGE_PDA_SEED, campaign_seed(&campaign_id)],
+        )?;
+        if is_initialized(accounts.config_change, program_id)? {
+            msg!("[Error] Pending config change must be cancelled first");
+            return Err(GaiminError::AccountInUse.into());
+        }
+
+        assert_derived_from(
+            accounts.epoch_schedule,
+            program_id,
+            &[EPOCH_SCHEDULE_PDA_SEED, campaign_seed(&campaign_id)],
+        )?;
+        if is_initialized(accounts.epoch_schedule, program_id)? {
+            msg!("[Error] Epoch schedule must be closed first");
+            return Err(GaiminError::AccountInUse.into());
+        }
+
+        assert_derived_from(
+            accounts.multisig,
+            program_id,
+            &[MULTISIG_PDA_SEED, campaign_seed(&campaign_id)],
+        )?;
+
+        // Receiver validation
+        assert_rent_receiver(&config.payer, accounts.receiver)?;
+
+        // The multisig may be the authority, so it is closed along with the config
+        if is_initialized(accounts.multisig, program_id)? {
+            close_account(accounts.multisig, accounts.receiver)?;
+        }
+
+        close_account(accounts.config, accounts.receiver)
+    }
+
+    fn process_close_multisig(
+        program_id: &Pubkey,
+        accounts: CloseMultisigAccounts,
+        signers: &[AccountInfo],
+    ) -> ProgramResult {
+        // Config validation
+        let config = load_config(accounts.config, program_id)?;
+        let campaign_id = config.campaign_id.to_le_bytes();
+
+        // Authority validation
+        assert_authority(program_id, &config.authority, accounts.authority, signers)?;
+
+        // Multisig validation
+        Loaded::<Multisig>::load(
+            accounts.multisig,
+            program_id,
+            &[MULTISIG_PDA_SEED, campaign_seed(&campaign_id)],
+        )?;
+
+        let holders = [
+            config.authority,
+            config.pending_authority,
+            config.registrar,
+            config.settler,
+        ];
+        if holders.contains(accounts.multisig.key) {
+            msg!("[Error] Multisig still holds a role of the campaign");
+            return Err(GaiminError::AccountInUse.into());
+        }
+
+        close_account(accounts.multisig, accounts.receiver)
+    }
+
+    fn process_close_epoch_schedule(
+        program_id: &Pubkey,
+        accounts: CloseEpochScheduleAccounts,
+        signers: &[AccountInfo],
+    ) -> ProgramResult {
+        // Config validation
+        let config = load_config(accounts.config, program_id)?;
+        let campaign_id = config.campaign_id.to_le_bytes();
+
+        // Authority validation
+        assert_authority(program_id, &config.authority, accounts.authority, signers)?;
+
+        // Epoch schedule validation
+        let schedule = Loaded::<EpochSchedule>::load(
+            accounts.epoch_schedule,
+            program_id,
+            &[EPOCH_SCHEDULE_PDA_SEED, campaign_seed(&campaign_id)],
+        )?;
+
+        // Epochs that have started were used to pay rewards of the remaining NFT records
+        let now = Clock::get()?.unix_timestamp;
+        let has_started = schedule
+            .epochs
+            .first()
+            .is_some_and(|epoch| epoch.starts_at <= now);
+        if has_started && config.nft_count > 0 {
+            msg!(
+                "[Error] Epoch schedule has started and the campaign still has {} NFT records",
+                config.nft_count
+            );
+            return Err(GaiminError::AccountInUse.into());
+        }
+
+        close_account(accounts.epoch_schedule, accounts.receiver)
+    }
+
+    fn process_close_nft_record(
+        program_id: &Pubkey,
+        accounts: CloseNftRecordAccounts,
+        signers: &[AccountInfo],
+    ) -> ProgramResult {
+        // Config validation
+        let mut config = load_config(accounts.config, program_id)?;
+        let campaign_id = config.campaign_id.to_le_bytes();
+
+        // Authority validation
+        assert_authority(program_id, &config.authority, accounts.authority, signers)?;
+
+        // NFT record validation
+        let nft_record = Loaded::<NftRecord>::load(
+            accounts.nft_record,
+            program_id,
+            &[
+                NFT_PDA_SEED,
+                campaign_seed(&campaign_id),
+                &accounts.mint.key.to_bytes(),
+            ],
+        )?;
+
+        // Receiver validation
+        assert_rent_receiver(&nft_record.payer, accounts.receiver)?;
+
+        // Config update. Records still in the version 0 layout were never counted
+        let is_counted = accounts.nft_record.data_len() == NftRecord::LEN;
+        if is_counted {
+            config.nft_count = config.nft_count.saturating_sub(1);
+        }
+        if config.emission_rate != 0 {
+            if nft_record.staked_since != 0 {
+                // The remaining NFTs share the pool from now on
+                reward::checkpoint(&mut config, Clock::get()?.unix_timestamp)?;
+                config.staked_count = config.staked_count.saturating_sub(1);
+            }
+        } else if is_counted {
+            config.allocated_reward = config.allocated_reward.saturating_sub(
+                nft_record
+                    .total_amount
+                    .saturating_sub(nft_record.claimed_amount),
+            );
+        }
+        config.save()?;
+
+        close_account(accounts.nft_record, accounts.receiver)
+    }
+
+    fn process_close_claim_record(
+        program_id: &Pubkey,
+        accounts: CloseClaimRecordAccounts,
+        signers: &[AccountInfo],
+    ) -> ProgramResult {
+        // Config validation
+        let config = load_config(accounts.config, program_id)?;
+
+        // Authority validation
+        if config.settler == *accounts.authority.key {
+            assert_authority(program_id, &config.settler, accounts.authority, signers)?;
+        } else {
+            assert_authority(program_id, &config.authority, accounts.authority, signers)?;
+        }
+
+        // Claim record validation
+        let claim = Loaded::<ClaimRecord>::load_unverified_address(accounts.claim, program_id)?;
+        if claim.campaign_id != config.campaign_id {
+            msg!("[Error] Claim record doesn't belong to this campaign");
+            return Err(GaiminError::PermissionDenied.into());
+        }
+
+        if !claim.settled {
+            msg!("[Error] Claim record hasn't been settled");
+            return Err(GaiminError::AccountInUse.into());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let current_generation = now.checked_div(config.generation_duration);
+        if current_generation.is_some_and(|generation| generation <= claim.generation) {
+            msg!(
+                "[Error] Claim record generation {} hasn't ended",
+                claim.generation
+            );
+            return Err(GaiminError::AccountInUse.into());
+        }
+
+        // Receiver validation
+        assert_rent_receiver(&claim.owner, accounts.receiver)?;
+
+        close_account(accounts.claim, accounts.receiver)
+    }
+
+    fn process_settle_claim_record(
+        program_id: &Pubkey,
+        accounts: SettleClaimRecordAccounts,
+        signers: &[AccountInfo],
+    ) -> ProgramResult {
+        // Config validation
+        let config = load_config(accounts.config, program_id)?;
+
+        // Authority validation
+        if config.settler == *accounts.authority.key {
+            assert_authority(program_id, &config.settler, accounts.authority, signers)?;
+        } else {
+            assert_authority(program_id, &config.authority, accounts.authority, signers)?;
+        }
+
+        // Claim record validation
+        let mut claim = Loaded::<ClaimRecord>::load_unverified_address(accounts.claim, program_id)?;
+        if claim.campaign_id != config.campaign_id {
+            msg!("[Error] Claim record doesn't belong to this campaign");
+            return Err(GaiminError::PermissionDenied.into());
+        } else if claim.settled {
+            msg!("[Error] Claim record has already been settled");
+            return Err(GaiminError::ClaimSettled.into());
+        }
+
+        // Claim record update
+        claim.settled = true;
+        claim.save()
+    }
+
+    fn process_set_role(
+        program_id: &Pubkey,
+        accounts: SetRoleAccounts,
+        signers: &[AccountInfo],
+        data: SetRoleArgs,
+    ) -> ProgramResult {
+        // Config validation
+        let mut config = load_config(accounts.config, program_id)?;
+
+        // Authority validation
+        assert_authority(program_id, &config.authority, accounts.authority, signers)?;
+
+        // Config update
+        match data.role {
+            Role::Registrar => config.registrar = *accounts.account.key,
+            Role::Settler => config.settler = *accounts.account.key,
+        }
+        config.save()
+    }
+
+    fn process_set_multisig(
+        program_id: &Pubkey,
+        accounts: SetMultisigAccounts,
+        signers: &[AccountInfo],
+        data: SetMultisigArgs,
+    ) -> ProgramResult {
+        // Config validation
+        let config = load_config(accounts.config, program_id)?;
+        let campaign_id = config.campaign_id.to_le_bytes();
+
+        // Authority validation
+        assert_authority(program_id, &config.authority, accounts.authority, signers)?;
+
+        // Signer set validation
+        let has_duplicates = data
+            .signers
+            .iter()
+            .enumerate()
+            .any(|(i, signer)| data.signers[..i].contains(signer));
+        if data.threshold == 0 || data.threshold as usize > data.signers.len() || has_duplicates {
+            msg!("[Error] Multisig signer set is invalid");
+            return Err(GaiminError::InvalidMultisig.into());
+        }
+
+        // Multisig validation
+        let bump = assert_derived_from(
+            accounts.multisig,
+            program_id,
+            &[MULTISIG_PDA_SEED, campaign_seed(&campaign_id)],
+        )?;
+
+        // Multisig update
+        if is_initialized(accounts.multisig, program_id)? {
+            let mut multisig =
+                Loaded::<Multisig>::load_unverified_address(accounts.multisig, program_id)?;
+            multisig.threshold = data.threshold;
+            multisig.signers = data.signers;
+            return multisig.save();
+        }
+
+        // Multisig creation
+        assert_signer(accounts.payer)?;
+        create_pda_account::<Multisig>(
+            accounts.multisig,
+            accounts.payer,
+            program_id,
+            &[MULTISIG_PDA_SEED, campaign_seed(&campaign_id), &[bump]],
+        )?;
+
+        Multisig::pack(
+            Multisig {
+                threshold: data.threshold,
+                signers: data.signers,
+            },
+            &mut accounts.multisig.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    fn process_set_epoch_schedule(
+        program_id: &Pubkey,
+        accounts: SetEpochScheduleAccounts,
+        signers: &[AccountInfo],
+        data: SetEpochScheduleArgs,
+    ) -> ProgramResult {
+        // Config validation. The pool shares a fixed emission, which epochs would change
+        let config = load_config(accounts.config, program_id)?;
+        let campaign_id = config.campaign_id.to_le_bytes();
+        if config.emission_rate != 0 {
+            msg!("[Error] Epochs aren't supported in pool mode");
+            return Err(GaiminError::InvalidConfig.into());
+        }
+
+        // Authority validation
+        assert_authority(program_id, &config.authority, accounts.authority, signers)?;
+
+        // Epoch schedule validation
+        let bump = assert_derived_from(
+            accounts.epoch_schedule,
+            program_id,
+            &[EPOCH_SCHEDULE_PDA_SEED, campaign_seed(&campaign_id)],
+        )?;
+
+        if data
+            .epochs
+            .windows(2)
+            .any(|pair| pair[0].starts_at >= pair[1].starts_at)
+        {
+            msg!("[Error] Epochs must be ordered by their start");
+            return Err(GaiminError::InvalidConfig.into());
+        }
+
+        // Epochs that have started were used to pay rewards, so they must be kept as they are.
+        // Changes to the rates only take effect after the timelock, like config changes
+        let locked_until = Clock::get()?
+            .unix_timestamp
+            .saturating_add(config.timelock_duration);
+        let started = |epochs: &[Epoch]| {
+            epochs
+                .iter()
+                .take_while(|epoch| epoch.starts_at <= locked_until)
+                .count()
+        };
+
+        let schedule = if is_initialized(accounts.epoch_schedule, program_id)? {
+            Some(Loaded::<EpochSchedule>::load_unverified_address(
+                accounts.epoch_schedule,
+                program_id,
+            )?)
+        } else {
+            None
+        };
+        let current_epochs = schedule
+            .as_ref()
+            .map_or(&[][..], |schedule| &schedule.epochs[..]);
+        let started_epochs = &current_epochs[..started(current_epochs)];
+
+        if data.epochs.get(..started_epochs.len()) != Some(started_epochs)
+            || started(&data.epochs) != started_epochs.len()
+        {
+            msg!(
+                "[Error] Epochs starting before {} can't be changed and new ones must start later",
+                locked_until
+            );
+            return Err(GaiminError::InvalidConfig.into());
+        }
+
+        // Epoch schedule update
+        if let Some(mut schedule) = schedule {
+            schedule.epochs = data.epochs;
+            return schedule.save();
+        }
+
+        // Epoch schedule creation
+        assert_signer(accounts.payer)?;
+        create_pda_account::<EpochSchedule>(
+            accounts.epoch_schedule,
+            accounts.payer,
+            program_id,
+            &[
+                EPOCH_SCHEDULE_PDA_SEED,
+                campaign_seed(&campaign_id),
+                &[bump],
+            ],
+        )?;
+
+        EpochSchedule::pack(
+            EpochSchedule {
+                epochs: data.epochs,
+            },
+            &mut accounts.epoch_schedule.try_borrow_mut_data()?,
+        )?;
 
-        delete_account(accounts.target, accounts.receiver)
+        Ok(())
     }
 
-    fn process_nft(program_id: &Pubkey, accounts: NftAccounts) -> ProgramResult {
+    fn process_migrate(
+        program_id: &Pubkey,
+        accounts: MigrateAccounts,
+        signers: &[AccountInfo],
+    ) -> ProgramResult {
         // Config validation
-        assert_derived_from(accounts.config, program_id, &[CONFIG_PDA_SEED])?;
-        assert_initialized(accounts.config)?;
+        let mut config = load_config(accounts.config, program_id)?;
 
-        let config = Config::unpack_unchecked(&accounts.config.try_borrow_data()?)?;
+        // Authority validation
+        assert_authority(program_id, &config.authority, accounts.authority, signers)?;
+
+        // Target validation
+        assert_owned_by(accounts.target, program_id)?;
+
+        // Account migration
+        let account_type = AccountType::of(&accounts.target.try_borrow_data()?);
+        match account_type {
+            AccountType::Config => {
+                if accounts.target.key != accounts.config.key {
+                    msg!("[Error] Config of another campaign cannot be migrated");
+                    return Err(GaiminError::PermissionDenied.into());
+                }
+                migrate_account::<Config>(accounts.target, accounts.payer)
+            }
+            AccountType::NftRecord => {
+                // Version 0 records belong to the default campaign, but not to a config created
+                // again after the migrated one was closed
+                if config.campaign_id != DEFAULT_CAMPAIGN_ID || config.payer != Pubkey::default() {
+                    msg!("[Error] NFT record doesn't belong to this campaign");
+                    return Err(GaiminError::PermissionDenied.into());
+                }
+                let nft_record = NftRecord::unpack_versioned(&accounts.target.try_borrow_data()?)?;
+                migrate_account::<NftRecord>(accounts.target, accounts.payer)?;
+
+                // Config update. Rewards claimed before the migration aren't part of
+                // `claimed_reward`, so only the unclaimed amount is allocated
+                config.nft_count = config
+                    .nft_count
+                    .checked_add(1)
+                    .ok_or(ProgramError::ArithmeticOverflow)?;
+                config.allocated_reward = config
+                    .allocated_reward
+                    .checked_add(
+                        nft_record
+                            .total_amount
+                            .saturating_sub(nft_record.claimed_amount),
+                    )
+                    .ok_or(ProgramError::ArithmeticOverflow)?;
+                config.save()
+            }
+            // Types introduced after the deployed program only have the current layout
+            AccountType::PendingConfigChange
+            | AccountType::Multisig
+            | AccountType::EpochSchedule
+            | AccountType::StakeLock => {
+                msg!("[Error] Account already uses the current layout");
+                Err(ProgramError::InvalidAccountData)
+            }
+            AccountType::ClaimRecord => {
+                let claim = ClaimRecord::unpack_versioned(&accounts.target.try_borrow_data()?)?;
+                if claim.campaign_id != config.campaign_id {
+                    msg!("[Error] Claim record doesn't belong to this campaign");
+                    return Err(GaiminError::PermissionDenied.into());
+                }
+                migrate_account::<ClaimRecord>(accounts.target, accounts.payer)
+            }
+            AccountType::Uninitialized => {
+                msg!("[Error] Account doesn't hold program data");
+                Err(GaiminError::InvalidAccountType.into())
+            }
+        }
+    }
+
+    fn process_nft(
+        program_id: &Pubkey,
+        accounts: NftAccounts,
+        remaining_accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        // Config validation
+        let mut config = load_config(accounts.config, program_id)?;
+        let campaign_id = config.campaign_id.to_le_bytes();
 
         // Authority validation
         assert_signer(accounts.payer)?;
@@ -139,12 +912,18 @@ impl Processor {
         let bump = assert_derived_from(
             accounts.nft_record,
             program_id,
-            &[NFT_PDA_SEED, &accounts.mint.key.to_bytes()],
+            &[
+                NFT_PDA_SEED,
+                campaign_seed(&campaign_id),
+                &accounts.mint.key.to_bytes(),
+            ],
         )?;
-        if is_initialized(accounts.nft_record)? {
+        if is_initialized(accounts.nft_record, program_id)? {
             return Ok(());
         }
 
+        assert_not_paused(&config, PAUSE_REGISTRATION)?;
+
         // Mint/Edition validation
         if *accounts.mint.owner != spl_token::id() {
             msg!("[Error] Mint address is not owned by the Token Program");
@@ -161,7 +940,7 @@ impl Processor {
                 b"edition",
             ],
         )?;
-        assert_initialized(accounts.edition)?;
+        assert_owned_by(accounts.edition, &MPL_TOKEN_METADATA_PROGRAM_ID)?;
 
         let mint = Mint::unpack_unchecked(&accounts.mint.try_borrow_data()?)?;
         if !mint.mint_authority.contains(accounts.edition.key) {
@@ -190,36 +969,83 @@ impl Processor {
             _ => return Err(GaiminError::InvalidTokenStandard.into()),
         }
 
-        let valid_creator = metadata.creators.map_or(false, |creators| {
+        let valid_creator = metadata.creators.is_some_and(|creators| {
             creators
                 .iter()
                 .any(|creator| creator.verified && creator.address == config.creator)
         });
-        if !valid_creator && &config.authority != accounts.payer.key {
-            return Err(GaiminError::InvalidCreator.into());
+        if !valid_creator && &config.registrar != accounts.payer.key {
+            // A registrar multisig can't sign as the payer, so it's passed separately
+            let (registrar, signers) = remaining_accounts
+                .split_first()
+                .ok_or(GaiminError::InvalidCreator)?;
+            if &config.registrar != registrar.key {
+                return Err(GaiminError::InvalidCreator.into());
+            }
+            assert_authority(program_id, &config.registrar, registrar, signers)?;
+        }
+
+        // Budget validation. In pool mode, rewards are only capped when claimed
+        let pool_mode = config.emission_rate != 0;
+        let total_amount = if pool_mode {
+            u64::MAX
+        } else {
+            config.initial_reward + config.accumulated_reward
+        };
+        let allocated_reward = if pool_mode {
+            config.allocated_reward
+        } else {
+            config
+                .allocated_reward
+                .checked_add(total_amount)
+                .ok_or(ProgramError::ArithmeticOverflow)?
+        };
+        if config.emission_budget != 0 && allocated_reward > config.emission_budget {
+            msg!(
+                "[Error] Only {} of the emission budget is left",
+                config
+                    .emission_budget
+                    .saturating_sub(config.allocated_reward)
+            );
+            return Err(GaiminError::BudgetExhausted.into());
         }
 
         // Account creation
-        invoke_signed(
-            &create_account_ix::<NftRecord>(
-                accounts.nft_record.key,
-                accounts.payer.key,
-                program_id,
-            ),
-            &[accounts.payer.clone(), accounts.nft_record.clone()],
-            &[&[NFT_PDA_SEED, &accounts.mint.key.to_bytes(), &[bump]]],
+        create_pda_account::<NftRecord>(
+            accounts.nft_record,
+            accounts.payer,
+            program_id,
+            &[
+                NFT_PDA_SEED,
+                campaign_seed(&campaign_id),
+                &accounts.mint.key.to_bytes(),
+                &[bump],
+            ],
         )?;
 
+        // Rewards only accrue once the NFT is staked, which moves `last_claim_at` forward
         NftRecord::pack(
             NftRecord {
                 claimed_amount: 0,
-                total_amount: config.initial_reward + config.accumulated_reward,
+                total_amount,
                 last_claim_at: config.claimable_from,
+                payer: *accounts.payer.key,
+                staked_since: 0,
+                staker: Pubkey::default(),
+                reward_index: Some(0),
+                reward_remainder: 0,
+                pending_reward: 0,
             },
             &mut accounts.nft_record.try_borrow_mut_data()?,
         )?;
 
-        Ok(())
+        // Config update
+        config.nft_count = config
+            .nft_count
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        config.allocated_reward = allocated_reward;
+        config.save()
     }
 
     fn process_create_claim(
@@ -231,35 +1057,43 @@ impl Processor {
         assert_signer(accounts.wallet)?;
 
         // Config validation
-        assert_derived_from(accounts.config, program_id, &[CONFIG_PDA_SEED])?;
-        assert_initialized(accounts.config)?;
+        let config = load_config(accounts.config, program_id)?;
+        let campaign_id = config.campaign_id.to_le_bytes();
+        assert_not_paused(&config, PAUSE_CREATE_CLAIM)?;
+
+        // Configs migrated from version 0 may predate the validation of the duration
+        let generation = Clock::get()?
+            .unix_timestamp
+            .checked_div(config.generation_duration)
+            .ok_or(GaiminError::InvalidConfig)?;
 
         // Claim record validation
         let claim_seeds_with_bump = &[
             CLAIM_PDA_SEED,
+            campaign_seed(&campaign_id),
             &accounts.wallet.key.to_bytes(),
             &data.seed,
             &[data.bump],
         ];
         assert_derived_from_with_bump(accounts.claim, program_id, claim_seeds_with_bump)?;
-        assert_uninitialized(accounts.claim)?;
+        assert_uninitialized(accounts.claim, program_id)?;
 
         // Claim record creation
-        invoke_signed(
-            &create_account_ix::<ClaimRecord>(accounts.claim.key, accounts.wallet.key, program_id),
-            &[accounts.wallet.clone(), accounts.claim.clone()],
-            &[claim_seeds_with_bump],
+        create_pda_account::<ClaimRecord>(
+            accounts.claim,
+            accounts.wallet,
+            program_id,
+            claim_seeds_with_bump,
         )?;
 
-        let config = Config::unpack_unchecked(&accounts.config.try_borrow_data()?)?;
-        let now = Clock::get()?.unix_timestamp as i32;
-
         ClaimRecord::pack(
             ClaimRecord {
-                generation: now / config.generation_duration,
+                generation,
                 amount: 0,
                 owner: *accounts.wallet.key,
                 bnb_chain_wallet_address: data.bnb_chain_wallet_address,
+                campaign_id: config.campaign_id,
+                settled: false,
             },
             &mut accounts.claim.try_borrow_mut_data()?,
         )?;
@@ -275,35 +1109,331 @@ impl Processor {
         // User wallet validation
         assert_signer(accounts.wallet)?;
 
+        // Token account validation. Its balance and lock are checked once the NFT record is loaded
+        let token = assert_token_account(accounts.wallet, accounts.token, data.token_acc_bump)?;
+
+        // Config validation
+        let mut config = load_config(accounts.config, program_id)?;
+        assert_not_paused(&config, PAUSE_CLAIM)?;
+        let now = Clock::get()?.unix_timestamp;
+
+        if now < config.claimable_from {
+            msg!("[Error] Claiming is not available yet");
+            return Err(GaiminError::ClaimingNotAvailable.into());
+        } else if now > config.claimable_until {
+            msg!("[Error] Claiming closed at {}", config.claimable_until);
+            return Err(GaiminError::ClaimingClosed.into());
+        }
+
+        // NFT record validation
+        let mut nft_record = Loaded::<NftRecord>::load_with_bump(
+            accounts.nft_record,
+            program_id,
+            &[
+                NFT_PDA_SEED,
+                campaign_seed(&config.campaign_id.to_le_bytes()),
+                &token.mint.to_bytes(),
+                &[data.nft_record_bump],
+            ],
+        )?;
+        // Unstaked NFTs can only be claimed for the reward settled on unstaking
+        let is_staked = nft_record.staked_since != 0;
+        if nft_record.staker != *accounts.wallet.key
+            || (!is_staked && nft_record.pending_reward == 0)
+        {
+            msg!("[Error] NFT is not staked by this wallet");
+            return Err(GaiminError::NotStaked.into());
+        } else if nft_record.claimed_amount >= nft_record.total_amount {
+            msg!("[Error] No claimable amount left");
+            return Err(GaiminError::AmountExhausted.into());
+        }
+
+        // Lock validation. Listed or rented out NFTs are locked by other delegates
+        if is_staked {
+            let token_record = load_token_record(
+                accounts.token_record,
+                accounts.token,
+                &token.mint,
+                data.token_record_bump,
+            )?;
+            if token_record.state != TokenState::Locked {
+                msg!("[Error] Token account is unlocked");
+                return Err(GaiminError::TokenAccountUnlocked.into());
+            }
+            let (delegate, _) = Pubkey::find_program_address(&[DELEGATE_PDA_SEED], program_id);
+            if !is_locked_by(&token_record, &delegate) {
+                msg!("[Error] Token is locked by a foreign delegate");
+                return Err(GaiminError::ForeignLock.into());
+            }
+        }
+
+        // Epoch schedule validation
+        let epochs = load_epochs(accounts.epoch_schedule, program_id, &config)?;
+
+        if nft_record.pending_reward == 0
+            && reward::is_accrual_finished(&config, &epochs, &nft_record)
+        {
+            msg!(
+                "[Error] Rewards stopped accruing at {}",
+                reward::accrual_ends_at(&config)
+            );
+            return Err(GaiminError::AccrualFinished.into());
+        }
+
+        let remaining_budget = reward::remaining_budget(&config);
+        if remaining_budget == 0 {
+            msg!("[Error] Emission budget has been claimed");
+            return Err(GaiminError::BudgetExhausted.into());
+        }
+
+        // Claim record validation
+        let mut claim = Loaded::<ClaimRecord>::load_unverified_address(accounts.claim, program_id)?;
+        if &claim.owner != accounts.wallet.key {
+            msg!("[Error] Claim record doesn't belong to this wallet");
+            return Err(GaiminError::PermissionDenied.into());
+        } else if claim.campaign_id != config.campaign_id {
+            msg!("[Error] Claim record doesn't belong to this campaign");
+            return Err(GaiminError::PermissionDenied.into());
+        } else if claim.settled {
+            msg!("[Error] Claim record has already been settled");
+            return Err(GaiminError::ClaimSettled.into());
+        }
+
+        // Reward calculation. Rewards beyond the budget are forfeited
+        let accrual = is_staked.then(|| reward::accrue(&config, &epochs, &nft_record, now));
+        let reward = u64::min(
+            nft_record
+                .pending_reward
+                .saturating_add(accrual.map_or(0, |accrual| accrual.reward)),
+            remaining_budget,
+        );
+
+        claim.amount = claim
+            .amount
+            .checked_add(reward)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        // Claim update
+        claim.save()?;
+
+        // NFT record update
+        if let Some(accrual) = accrual {
+            nft_record.last_claim_at = accrual.last_claim_at;
+            nft_record.reward_index = Some(accrual.reward_index);
+            nft_record.reward_remainder = accrual.reward_remainder;
+        }
+        nft_record.pending_reward = 0;
+        nft_record.claimed_amount += reward;
+        nft_record.save()?;
+
+        // Config update
+        config.claimed_reward = config.claimed_reward.saturating_add(reward);
+        config.save()
+    }
+
+    fn process_stake(
+        program_id: &Pubkey,
+        accounts: StakeAccounts,
+        data: StakeArgs,
+    ) -> ProgramResult {
+        // User wallet validation
+        assert_signer(accounts.wallet)?;
+
         // Token account validation
-        if *accounts.token.owner != spl_token::id() {
-            msg!("[Error] Token account does not belong to the Token Program");
+        let token = assert_token_holder(accounts.wallet, accounts.token, data.token_acc_bump)?;
+        if &token.mint != accounts.mint.key {
+            msg!("[Error] Token account holds another NFT");
             return Err(GaiminError::InvalidTokenAccount.into());
         }
+        let token_record = load_token_record(
+            accounts.token_record,
+            accounts.token,
+            &token.mint,
+            data.token_record_bump,
+        )?;
 
-        let token = Account::unpack_unchecked(&accounts.token.try_borrow_data()?)?;
+        // Config validation
+        let mut config = load_config(accounts.config, program_id)?;
+        let campaign_id = config.campaign_id.to_le_bytes();
+        assert_not_paused(&config, PAUSE_STAKE)?;
+        let now = Clock::get()?.unix_timestamp;
 
-        assert_derived_from_with_bump(
-            accounts.token,
-            &SPL_ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID,
+        // NFT record validation
+        let mut nft_record = Loaded::<NftRecord>::load_with_bump(
+            accounts.nft_record,
+            program_id,
             &[
-                &accounts.wallet.key.to_bytes(),
-                &spl_token::ID.to_bytes(),
+                NFT_PDA_SEED,
+                campaign_seed(&campaign_id),
                 &token.mint.to_bytes(),
-                &[data.token_acc_bump],
+                &[data.nft_record_bump],
             ],
         )?;
+        if nft_record.staked_since != 0 {
+            if nft_record.staker == *accounts.wallet.key {
+                msg!("[Error] NFT is already staked by this wallet");
+            } else {
+                msg!("[Error] NFT is staked by {}", nft_record.staker);
+            }
+            return Err(GaiminError::AlreadyStaked.into());
+        }
+
+        // Delegate validation. The same delegate locks the token for every campaign
+        let delegate_bump =
+            assert_derived_from(accounts.delegate, program_id, &[DELEGATE_PDA_SEED])?;
+        assert_token_metadata_program(accounts.token_metadata_program)?;
 
-        if &token.owner != accounts.wallet.key {
+        // Stake lock validation. The campaign is still listed if its NFT record has been closed
+        // while the NFT was staked
+        let stake_lock_bump = assert_derived_from(
+            accounts.stake_lock,
+            program_id,
+            &[STAKE_LOCK_PDA_SEED, &token.mint.to_bytes()],
+        )?;
+        let stake_lock = if is_initialized(accounts.stake_lock, program_id)? {
+            Some(Loaded::<StakeLock>::load_unverified_address(
+                accounts.stake_lock,
+                program_id,
+            )?)
+        } else {
+            None
+        };
+        let mut campaign_ids = stake_lock
+            .as_ref()
+            .map_or(vec![], |stake_lock| stake_lock.campaign_ids.clone());
+        if !campaign_ids.contains(&config.campaign_id) {
+            if campaign_ids.len() == MAX_STAKED_CAMPAIGNS {
+                msg!(
+                    "[Error] NFT is already staked in {} campaigns",
+                    MAX_STAKED_CAMPAIGNS
+                );
+                return Err(GaiminError::TooManyCampaigns.into());
+            }
+            campaign_ids.push(config.campaign_id);
+        }
+
+        // Token lock. The token is already locked by the delegate if the NFT is staked in another
+        // campaign. Locks of other delegates can't be verified to last while the NFT is staked
+        if token_record.state != TokenState::Unlocked {
+            if !is_locked_by(&token_record, accounts.delegate.key) {
+                msg!("[Error] Token is locked by a foreign delegate");
+                return Err(GaiminError::ForeignLock.into());
+            }
+        } else {
+            DelegateStakingV1Cpi::new(
+                accounts.token_metadata_program,
+                DelegateStakingV1CpiAccounts {
+                    delegate_record: None,
+                    delegate: accounts.delegate,
+                    metadata: accounts.metadata,
+                    master_edition: Some(accounts.edition),
+                    token_record: Some(accounts.token_record),
+                    mint: accounts.mint,
+                    token: accounts.token,
+                    authority: accounts.wallet,
+                    payer: accounts.wallet,
+                    system_program: accounts.system_program,
+                    sysvar_instructions: accounts.sysvar_instructions,
+                    spl_token_program: Some(accounts.spl_token_program),
+                    authorization_rules_program: accounts.authorization_rules_program,
+                    authorization_rules: accounts.authorization_rules,
+                },
+                DelegateStakingV1InstructionArgs {
+                    amount: 1,
+                    authorization_data: None,
+                },
+            )
+            .invoke()?;
+
+            LockV1Cpi::new(
+                accounts.token_metadata_program,
+                LockV1CpiAccounts {
+                    authority: accounts.delegate,
+                    token_owner: Some(accounts.wallet),
+                    token: accounts.token,
+                    mint: accounts.mint,
+                    metadata: accounts.metadata,
+                    edition: Some(accounts.edition),
+                    token_record: Some(accounts.token_record),
+                    payer: accounts.wallet,
+                    system_program: accounts.system_program,
+                    sysvar_instructions: accounts.sysvar_instructions,
+                    spl_token_program: Some(accounts.spl_token_program),
+                    authorization_rules_program: accounts.authorization_rules_program,
+                    authorization_rules: accounts.authorization_rules,
+                },
+                LockV1InstructionArgs {
+                    authorization_data: None,
+                },
+            )
+            .invoke_signed(&[&[DELEGATE_PDA_SEED, &[delegate_bump]]])?;
+        }
+
+        // Stake lock update
+        match stake_lock {
+            Some(mut stake_lock) => {
+                stake_lock.campaign_ids = campaign_ids;
+                stake_lock.save()?;
+            }
+            None => {
+                create_pda_account::<StakeLock>(
+                    accounts.stake_lock,
+                    accounts.wallet,
+                    program_id,
+                    &[
+                        STAKE_LOCK_PDA_SEED,
+                        &token.mint.to_bytes(),
+                        &[stake_lock_bump],
+                    ],
+                )?;
+                StakeLock::pack(
+                    StakeLock { campaign_ids },
+                    &mut accounts.stake_lock.try_borrow_mut_data()?,
+                )?;
+            }
+        }
+
+        // Pool update
+        if config.emission_rate != 0 {
+            reward::checkpoint(&mut config, now)?;
+            config.staked_count = config
+                .staked_count
+                .checked_add(1)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+
+        // NFT record update. Rewards accrue from now on
+        let last_claim_at =
+            i64::max(now, config.claimable_from).min(reward::accrual_ends_at(&config));
+        nft_record.last_claim_at = i64::max(nft_record.last_claim_at, last_claim_at);
+        nft_record.reward_index = Some(reward::reward_index(&config, nft_record.last_claim_at));
+        nft_record.reward_remainder = 0;
+        if nft_record.staker != *accounts.wallet.key {
+            // The reward settled for the previous staker is forfeited
+            nft_record.pending_reward = 0;
+        }
+        nft_record.staked_since = now;
+        nft_record.staker = *accounts.wallet.key;
+        nft_record.save()?;
+
+        config.save()
+    }
+
+    fn process_unstake(program_id: &Pubkey, accounts: UnstakeAccounts) -> ProgramResult {
+        // Staker wallet validation
+        assert_signer(accounts.wallet)?;
+
+        // Token account validation. The Token Metadata program validates the rest
+        if *accounts.token.owner != spl_token::id() {
+            msg!("[Error] Token account does not belong to the Token Program");
+            return Err(GaiminError::InvalidTokenAccount.into());
+        }
+        let token = Account::unpack_unchecked(&accounts.token.try_borrow_data()?)?;
+        if &token.owner != accounts.wallet.key || &token.mint != accounts.mint.key {
             msg!("[Error] Token account does not belong to the user");
             return Err(GaiminError::InvalidTokenAccount.into());
-        } else if token.amount == 0 {
-            msg!("[Error] Token account does not hold the NFT");
-            return Err(GaiminError::ZeroNftBalance.into());
         }
-
-        // Token record validation
-        assert_derived_from_with_bump(
+        assert_derived_from(
             accounts.token_record,
             &MPL_TOKEN_METADATA_PROGRAM_ID,
             &[
@@ -312,75 +1442,434 @@ impl Processor {
                 &token.mint.to_bytes(),
                 b"token_record",
                 &accounts.token.key.to_bytes(),
-                &[data.token_record_bump],
             ],
         )?;
-
         let token_record =
             TokenRecord::safe_deserialize(&accounts.token_record.try_borrow_data()?)?;
-        if token_record.state != TokenState::Locked {
-            msg!("[Error] Token account is unlocked");
-            return Err(GaiminError::TokenAccountUnlocked.into());
-        }
 
         // Config validation
-        assert_derived_from(accounts.config, program_id, &[CONFIG_PDA_SEED])?;
-        assert_initialized(accounts.config)?;
-
-        let config = Config::unpack_unchecked(&accounts.config.try_borrow_data()?)?;
-        let now = Clock::get()?.unix_timestamp as i32;
-
-        if now < config.claimable_from {
-            msg!("[Error] Claiming is not available yet");
-            return Err(GaiminError::ClaimingNotAvailable.into());
-        }
+        let mut config = load_config(accounts.config, program_id)?;
+        let campaign_id = config.campaign_id.to_le_bytes();
 
-        // NFT record validation
-        assert_derived_from_with_bump(
+        // NFT record update. A closed record may leave the token locked, which is released below
+        assert_derived_from(
             accounts.nft_record,
             program_id,
             &[
                 NFT_PDA_SEED,
-                &token.mint.to_bytes(),
-                &[data.nft_record_bump],
+                campaign_seed(&campaign_id),
+                &accounts.mint.key.to_bytes(),
             ],
         )?;
-        assert_initialized(accounts.nft_record)?;
+        if is_initialized(accounts.nft_record, program_id)? {
+            let mut nft_record =
+                Loaded::<NftRecord>::load_unverified_address(accounts.nft_record, program_id)?;
+            if nft_record.staked_since == 0 || nft_record.staker != *accounts.wallet.key {
+                msg!("[Error] NFT is not staked by this wallet");
+                return Err(GaiminError::NotStaked.into());
+            }
 
-        let mut nft_record = NftRecord::unpack_unchecked(&accounts.nft_record.try_borrow_data()?)?;
-        if nft_record.claimed_amount >= nft_record.total_amount {
-            msg!("[Error] No claimable amount left");
-            return Err(GaiminError::AmountExhausted.into());
+            // Reward settlement. The staker claims the accrued reward later, see
+            // `process_claim`
+            let now = Clock::get()?.unix_timestamp;
+            let epochs = load_epochs(accounts.epoch_schedule, program_id, &config)?;
+            let accrual = reward::accrue(&config, &epochs, &nft_record, now);
+            nft_record.pending_reward = nft_record
+                .pending_reward
+                .checked_add(accrual.reward)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            nft_record.last_claim_at = accrual.last_claim_at;
+            nft_record.reward_index = Some(accrual.reward_index);
+            nft_record.reward_remainder = accrual.reward_remainder;
+
+            // Pool update. The remaining NFTs share the pool from now on
+            if config.emission_rate != 0 {
+                reward::checkpoint(&mut config, now)?;
+                config.staked_count = config.staked_count.saturating_sub(1);
+            }
+
+            nft_record.staked_since = 0;
+            nft_record.save()?;
+            config.save()?;
         }
 
-        // Claim record validation
-        assert_initialized(accounts.claim)?;
+        // Stake lock update. The token stays locked while the NFT is staked in other campaigns
+        assert_derived_from(
+            accounts.stake_lock,
+            program_id,
+            &[STAKE_LOCK_PDA_SEED, &accounts.mint.key.to_bytes()],
+        )?;
+        let has_stake_lock = is_initialized(accounts.stake_lock, program_id)?;
+        if has_stake_lock {
+            let mut stake_lock =
+                Loaded::<StakeLock>::load_unverified_address(accounts.stake_lock, program_id)?;
+            stake_lock
+                .campaign_ids
+                .retain(|&campaign_id| campaign_id != config.campaign_id);
+            if !stake_lock.campaign_ids.is_empty() {
+                return stake_lock.save();
+            }
+        }
 
-        let mut claim = ClaimRecord::unpack_unchecked(*accounts.claim.try_borrow_data()?)?;
+        // Delegate validation
+        let delegate_bump =
+            assert_derived_from(accounts.delegate, program_id, &[DELEGATE_PDA_SEED])?;
+        assert_token_metadata_program(accounts.token_metadata_program)?;
 
-        if &claim.owner != accounts.wallet.key {
-            msg!("[Error] Claim record doesn't belong to this wallet");
-            return Err(GaiminError::PermissionDenied.into());
+        // Token unlock. Tokens locked before staking was done by the program are left as they are
+        if is_locked_by(&token_record, accounts.delegate.key) {
+            UnlockV1Cpi::new(
+                accounts.token_metadata_program,
+                UnlockV1CpiAccounts {
+                    authority: accounts.delegate,
+                    token_owner: Some(accounts.wallet),
+                    token: accounts.token,
+                    mint: accounts.mint,
+                    metadata: accounts.metadata,
+                    edition: Some(accounts.edition),
+                    token_record: Some(accounts.token_record),
+                    payer: accounts.wallet,
+                    system_program: accounts.system_program,
+                    sysvar_instructions: accounts.sysvar_instructions,
+                    spl_token_program: Some(accounts.spl_token_program),
+                    authorization_rules_program: accounts.authorization_rules_program,
+                    authorization_rules: accounts.authorization_rules,
+                },
+                UnlockV1InstructionArgs {
+                    authorization_data: None,
+                },
+            )
+            .invoke_signed(&[&[DELEGATE_PDA_SEED, &[delegate_bump]]])?;
+
+            RevokeStakingV1Cpi::new(
+                accounts.token_metadata_program,
+                RevokeStakingV1CpiAccounts {
+                    delegate_record: None,
+                    delegate: accounts.delegate,
+                    metadata: accounts.metadata,
+                    master_edition: Some(accounts.edition),
+                    token_record: Some(accounts.token_record),
+                    mint: accounts.mint,
+                    token: accounts.token,
+                    authority: accounts.wallet,
+                    payer: accounts.wallet,
+                    system_program: accounts.system_program,
+                    sysvar_instructions: accounts.sysvar_instructions,
+                    spl_token_program: Some(accounts.spl_token_program),
+                    authorization_rules_program: accounts.authorization_rules_program,
+                    authorization_rules: accounts.authorization_rules,
+                },
+            )
+            .invoke()?;
         }
 
-        // Reward calculation
-        let base_reward = if nft_record.claimed_amount == 0 { config.initial_reward } else { 0 };
-        let stake_duration = now - nft_record.last_claim_at;
-        let reward = i32::min(
-            nft_record.total_amount - nft_record.claimed_amount,
-            base_reward + (stake_duration / config.accumulation_duration),
-        );
+        // Stake lock closure. The lamports are only moved after the CPIs, which don't see the
+        // stake lock account
+        if has_stake_lock {
+            close_account(accounts.stake_lock, accounts.wallet)?;
+        }
 
-        claim.amount += reward;
+        Ok(())
+    }
+}
 
-        // Claim update
-        ClaimRecord::pack(claim, &mut accounts.claim.try_borrow_mut_data()?)?;
+fn apply_config_change(config: &mut Config, change: &ConfigChangeArgs) {
+    if let Some(creator) = change.creator {
+        config.creator = creator;
+    }
+    if let Some(claimable_from) = change.claimable_from {
+        config.claimable_from = claimable_from;
+    }
+    if let Some(claimable_until) = change.claimable_until {
+        config.claimable_until = claimable_until;
+    }
+    if let Some(accrual_ends_at) = change.accrual_ends_at {
+        config.accrual_ends_at = accrual_ends_at;
+    }
+    if let Some(accumulated_reward) = change.accumulated_reward {
+        config.accumulated_reward = accumulated_reward;
+    }
+    if let Some(initial_reward) = change.initial_reward {
+        config.initial_reward = initial_reward;
+    }
+    if let Some(accumulation_duration) = change.accumulation_duration {
+        config.accumulation_duration = accumulation_duration;
+    }
+    if let Some(generation_duration) = change.generation_duration {
+        config.generation_duration = generation_duration;
+    }
+    if let Some(timelock_duration) = change.timelock_duration {
+        config.timelock_duration = timelock_duration;
+    }
+    if let Some(reward_curve) = &change.reward_curve {
+        config.reward_curve = reward_curve.clone();
+    }
+    if let Some(emission_budget) = change.emission_budget {
+        config.emission_budget = emission_budget;
+    }
+    if let Some(boost_schedule) = &change.boost_schedule {
+        config.boost_schedule = boost_schedule.clone();
+    }
+    if let Some(emission_rate) = change.emission_rate {
+        config.emission_rate = emission_rate;
+    }
+}
 
-        // NFT record update
-        nft_record.last_claim_at = now;
-        nft_record.claimed_amount += reward;
-        NftRecord::pack(nft_record, &mut accounts.nft_record.try_borrow_mut_data()?)?;
+/// Checks whether the change affects the growth of [`Config::reward_index`]
+fn changes_rate(change: &ConfigChangeArgs) -> bool {
+    change.claimable_from.is_some()
+        || change.claimable_until.is_some()
+        || change.accrual_ends_at.is_some()
+        || change.accumulation_duration.is_some()
+        || change.reward_curve.is_some()
+        || change.emission_rate.is_some()
+}
+
+fn validate_config_change(config: &Config, change: &ConfigChangeArgs) -> ProgramResult {
+    // NFT records are created differently in pool mode, so the mode is fixed for a campaign
+    if change
+        .emission_rate
+        .is_some_and(|emission_rate| (emission_rate == 0) != (config.emission_rate == 0))
+    {
+        msg!("[Error] Pool mode can't be switched on or off");
+        return Err(GaiminError::InvalidConfig.into());
+    }
+
+    Ok(())
+}
+
+fn validate_config(config: &Config) -> ProgramResult {
+    // Pools accrue by their emission rate, so the accumulation duration isn't used
+    if (config.emission_rate == 0 && config.accumulation_duration <= 0)
+        || config.generation_duration <= 0
+        || config.timelock_duration < 0
+        || config.claimable_until < config.claimable_from
+        || (config.emission_budget != 0 && config.emission_budget < config.claimed_reward)
+        || (config.accrual_ends_at != 0
+            && (config.accrual_ends_at < config.claimable_from
+                || config.accrual_ends_at > config.claimable_until))
+    {
+        msg!("[Error] Config data is invalid");
+        return Err(GaiminError::InvalidConfig.into());
+    }
+
+    let is_valid_curve = match &config.reward_curve {
+        RewardCurve::Linear => true,
+        RewardCurve::CliffLinear { cliff } => *cliff >= 0,
+        RewardCurve::ExponentialDecay { half_life } => *half_life > 0,
+        RewardCurve::Tiered(tiers) => {
+            !tiers.is_empty()
+                && tiers.len() <= MAX_REWARD_TIERS
+                && tiers[0].starts_after >= 0
+                && tiers
+                    .windows(2)
+                    .all(|pair| pair[0].starts_after < pair[1].starts_after)
+        }
+    };
+    if !is_valid_curve {
+        msg!("[Error] Reward curve is invalid");
+        return Err(GaiminError::InvalidConfig.into());
+    }
+
+    // The pool shares a fixed emission, which boosts would exceed
+    let tiers = &config.boost_schedule.tiers;
+    if config.emission_rate != 0 && !tiers.is_empty() {
+        msg!("[Error] Boosts aren't supported in pool mode");
+        return Err(GaiminError::InvalidConfig.into());
+    }
+    if tiers.len() > MAX_BOOST_TIERS
+        || tiers
+            .iter()
+            .any(|tier| tier.staked_for <= 0 || tier.multiplier_bps == 0)
+        || tiers
+            .windows(2)
+            .any(|pair| pair[0].staked_for >= pair[1].staked_for)
+    {
+        msg!("[Error] Boost schedule is invalid");
+        return Err(GaiminError::InvalidConfig.into());
+    }
+
+    config
+        .initial_reward
+        .checked_add(config.accumulated_reward)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok(())
+}
 
+/// Returns the seed that namespaces the accounts of a campaign. The default campaign uses an empty
+/// seed, so its accounts keep the addresses they had before campaigns were introduced
+pub fn campaign_seed(campaign_id: &[u8; 8]) -> &[u8] {
+    if u64::from_le_bytes(*campaign_id) == DEFAULT_CAMPAIGN_ID {
+        &[]
+    } else {
+        campaign_id
+    }
+}
+
+/// Checks that rent is returned to the account who paid it. Accounts that don't store their payer
+/// may return it to any account
+fn assert_rent_receiver(payer: &Pubkey, receiver: &AccountInfo) -> ProgramResult {
+    if *payer != Pubkey::default() && payer != receiver.key {
+        msg!("[Error] Lamports must be returned to {}", payer);
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(())
+}
+
+/// Checks that `token` is the associated token account of `wallet` and holds the NFT, and returns
+/// it
+fn assert_token_holder(
+    wallet: &AccountInfo,
+    token: &AccountInfo,
+    token_acc_bump: u8,
+) -> Result<Account, ProgramError> {
+    let token_acc = assert_token_account(wallet, token, token_acc_bump)?;
+    if token_acc.amount == 0 {
+        msg!("[Error] Token account does not hold the NFT");
+        return Err(GaiminError::ZeroNftBalance.into());
+    }
+
+    Ok(token_acc)
+}
+
+/// Checks that `token` is the associated token account of `wallet`, which may no longer hold the
+/// NFT, and returns it
+fn assert_token_account(
+    wallet: &AccountInfo,
+    token: &AccountInfo,
+    token_acc_bump: u8,
+) -> Result<Account, ProgramError> {
+    if *token.owner != spl_token::id() {
+        msg!("[Error] Token account does not belong to the Token Program");
+        return Err(GaiminError::InvalidTokenAccount.into());
+    }
+
+    let token_acc = Account::unpack_unchecked(&token.try_borrow_data()?)?;
+
+    assert_derived_from_with_bump(
+        token,
+        &SPL_ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID,
+        &[
+            &wallet.key.to_bytes(),
+            &spl_token::ID.to_bytes(),
+            &token_acc.mint.to_bytes(),
+            &[token_acc_bump],
+        ],
+    )?;
+
+    if &token_acc.owner != wallet.key {
+        msg!("[Error] Token account does not belong to the user");
+        return Err(GaiminError::InvalidTokenAccount.into());
+    }
+
+    Ok(token_acc)
+}
+
+/// Returns the epochs of the campaign of `config`, which has none if its epoch schedule isn't
+/// initialized
+fn load_epochs(
+    epoch_schedule: &AccountInfo,
+    program_id: &Pubkey,
+    config: &Config,
+) -> Result<Vec<Epoch>, ProgramError> {
+    assert_derived_from(
+        epoch_schedule,
+        program_id,
+        &[
+            EPOCH_SCHEDULE_PDA_SEED,
+            campaign_seed(&config.campaign_id.to_le_bytes()),
+        ],
+    )?;
+    if !is_initialized(epoch_schedule, program_id)? {
+        return Ok(vec![]);
+    }
+
+    Ok(
+        Loaded::<EpochSchedule>::load_unverified_address(epoch_schedule, program_id)?
+            .epochs
+            .clone(),
+    )
+}
+
+/// Returns the token record of `token`, which holds an NFT of `mint`
+fn load_token_record(
+    token_record: &AccountInfo,
+    token: &AccountInfo,
+    mint: &Pubkey,
+    token_record_bump: u8,
+) -> Result<TokenRecord, ProgramError> {
+    assert_derived_from_with_bump(
+        token_record,
+        &MPL_TOKEN_METADATA_PROGRAM_ID,
+        &[
+            b"metadata",
+            &MPL_TOKEN_METADATA_PROGRAM_ID.to_bytes(),
+            &mint.to_bytes(),
+            b"token_record",
+            &token.key.to_bytes(),
+            &[token_record_bump],
+        ],
+    )?;
+
+    Ok(TokenRecord::safe_deserialize(
+        &token_record.try_borrow_data()?,
+    )?)
+}
+
+/// Returns whether the token has been locked by `delegate`
+fn is_locked_by(token_record: &TokenRecord, delegate: &Pubkey) -> bool {
+    token_record.state == TokenState::Locked
+        && token_record.delegate == Some(*delegate)
+        && token_record.delegate_role == Some(TokenDelegateRole::Staking)
+}
+
+fn assert_token_metadata_program(acc: &AccountInfo) -> ProgramResult {
+    if acc.key != &MPL_TOKEN_METADATA_PROGRAM_ID {
+        msg!("[Error] Expected the Token Metadata program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+fn assert_not_paused(config: &Config, operation: u8) -> ProgramResult {
+    if config.paused & operation != 0 {
+        msg!("[Error] Operation is paused");
+        Err(GaiminError::Paused.into())
+    } else {
         Ok(())
     }
 }
+
+fn load_config<'a, 'info>(
+    acc: &'a AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<Loaded<'a, 'info, Config>, ProgramError> {
+    // The campaign ID is only known once the config is read
+    let config = Loaded::<Config>::load_unverified_address(acc, program_id)?;
+    assert_derived_from(
+        acc,
+        program_id,
+        &[
+            CONFIG_PDA_SEED,
+            campaign_seed(&config.campaign_id.to_le_bytes()),
+        ],
+    )?;
+
+    Ok(config)
+}
+
+fn load_config_change<'a, 'info>(
+    acc: &'a AccountInfo<'info>,
+    config: &Config,
+    program_id: &Pubkey,
+) -> Result<Loaded<'a, 'info, PendingConfigChange>, ProgramError> {
+    Loaded::load(
+        acc,
+        program_id,
+        &[
+            CONFIG_CHANGE_PDA_SEED,
+            campaign_seed(&config.campaign_id.to_le_bytes()),
+        ],
+    )
+}
@@ -0,0 +1,321 @@
+//! Reward accrual of NFT records. The functions are pure, so that payouts can be computed without
+//! any accounts
+
+use solana_program::{entrypoint::ProgramResult, msg};
+
+use crate::{
+    error::GaiminError,
+    state::{
+        Config, Epoch, IndexCheckpoint, NftRecord, RewardCurve, BOOST_BASE, EPOCH_RATE_BASE,
+        MAX_INDEX_CHECKPOINTS, REWARD_INDEX_SCALE,
+    },
+};
+
+/// Units of a base unit in which earned rewards are computed before being rounded down, so that
+/// boosts and epoch rates don't lose precision
+pub const EARNED_SCALE: u128 = REWARD_INDEX_SCALE * BOOST_BASE as u128 * EPOCH_RATE_BASE as u128;
+
+/// Result of a claim for a single NFT
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Accrual {
+    /// Reward to be added to the claim record, in base units
+    pub reward: u64,
+
+    /// New value of [`NftRecord::last_claim_at`]
+    pub last_claim_at: i64,
+
+    /// New value of [`NftRecord::reward_index`]
+    pub reward_index: u128,
+
+    /// New value of [`NftRecord::reward_remainder`]
+    pub reward_remainder: u64,
+}
+
+/// Returns the date after which rewards stop accruing
+pub fn accrual_ends_at(config: &Config) -> i64 {
+    if config.accrual_ends_at != 0 {
+        config.accrual_ends_at
+    } else {
+        config.claimable_until
+    }
+}
+
+/// Returns the reward accrued by an NFT from [`IndexCheckpoint::claimable_from`] until `at`
+/// according to the reward curve of `rate`, in units of [`REWARD_INDEX_SCALE`]. It never decreases
+/// as `at` grows
+fn curve_index(rate: &IndexCheckpoint, at: i64) -> u128 {
+    let (Ok(elapsed), Ok(duration)) = (
+        u128::try_from(at.saturating_sub(rate.claimable_from)),
+        u128::try_from(rate.accumulation_duration),
+    ) else {
+        return 0;
+    };
+    if duration == 0 {
+        return 0;
+    }
+
+    match &rate.reward_curve {
+        RewardCurve::Linear => elapsed.saturating_mul(REWARD_INDEX_SCALE) / duration,
+        RewardCurve::CliffLinear { cliff } => {
+            if elapsed < u128::try_from(*cliff).unwrap_or_default() {
+                0
+            } else {
+                elapsed.saturating_mul(REWARD_INDEX_SCALE) / duration
+            }
+        }
+        RewardCurve::ExponentialDecay { half_life } => {
+            let half_life = match u128::try_from(*half_life) {
+                Ok(half_life) if half_life > 0 => half_life,
+                _ => return 0,
+            };
+
+            // Sum of the halving rates over `halvings` whole half-lives and the remainder, scaled
+            // by `2^halvings`. The rate becomes negligible after 62 halvings, which also bounds
+            // the scale to fit in 128 bits
+            let (halvings, remainder) = match elapsed / half_life {
+                halvings if halvings > 62 => (62, half_life),
+                halvings => (halvings as u32, elapsed % half_life),
+            };
+            (half_life * ((1 << (halvings + 1)) - 2) + remainder).saturating_mul(REWARD_INDEX_SCALE)
+                / (duration << halvings)
+        }
+        RewardCurve::Tiered(tiers) => {
+            let mut total = 0u128;
+            for (i, tier) in tiers.iter().enumerate() {
+                let starts_after = u128::try_from(tier.starts_after).unwrap_or_default();
+                let ends_after = tiers.get(i + 1).map_or(u128::MAX, |next| {
+                    u128::try_from(next.starts_after).unwrap_or_default()
+                });
+                let tier_elapsed = elapsed.min(ends_after).saturating_sub(starts_after);
+                total = total.saturating_add(u128::from(tier.rate) * tier_elapsed);
+            }
+            total.saturating_mul(REWARD_INDEX_SCALE) / duration
+        }
+    }
+}
+
+/// Returns the reward emitted by the pool per staked NFT from [`Config::index_updated_at`] until
+/// `at`, in units of [`REWARD_INDEX_SCALE`]. Nothing is emitted before [`Config::claimable_from`],
+/// after [`accrual_ends_at`] or while no NFTs are staked
+fn pool_index(config: &Config, at: i64) -> u128 {
+    let from = i64::max(config.index_updated_at, config.claimable_from);
+    let at = i64::min(at, accrual_ends_at(config));
+    let Ok(elapsed) = u128::try_from(at.saturating_sub(from)) else {
+        return 0;
+    };
+    if config.staked_count == 0 {
+        return 0;
+    }
+
+    elapsed
+        .saturating_mul(config.emission_rate.into())
+        .saturating_mul(REWARD_INDEX_SCALE)
+        / u128::from(config.staked_count)
+}
+
+/// Returns the latest checkpoint of [`Config::reward_index`] with the current rate
+fn latest_checkpoint(config: &Config) -> IndexCheckpoint {
+    IndexCheckpoint {
+        at: config.index_updated_at,
+        index: config.reward_index,
+        claimable_from: config.claimable_from,
+        accrual_ends_at: accrual_ends_at(config),
+        accumulation_duration: config.accumulation_duration,
+        reward_curve: config.reward_curve.clone(),
+    }
+}
+
+/// Returns the reward index at `at` from its value at `checkpoint` and the rate in force since.
+/// It doesn't grow after [`IndexCheckpoint::accrual_ends_at`]
+fn index_since(checkpoint: &IndexCheckpoint, at: i64) -> u128 {
+    let from = i64::min(checkpoint.at, checkpoint.accrual_ends_at);
+    let at = i64::min(at, checkpoint.accrual_ends_at);
+    let start = curve_index(checkpoint, from);
+    let index = curve_index(checkpoint, at);
+    if at >= from {
+        checkpoint.index.saturating_add(index.saturating_sub(start))
+    } else {
+        checkpoint.index.saturating_sub(start.saturating_sub(index))
+    }
+}
+
+/// Returns the reward index at `at`, see [`Config::reward_index`]. It doesn't grow after
+/// [`accrual_ends_at`]. Values before [`Config::index_updated_at`] are computed at the rate of
+/// the time, see [`Config::index_history`], except in pool mode
+pub fn reward_index(config: &Config, at: i64) -> u128 {
+    if config.emission_rate != 0 {
+        return config.reward_index.saturating_add(pool_index(config, at));
+    }
+
+    let previous = config
+        .index_history
+        .iter()
+        .rev()
+        .find(|checkpoint| checkpoint.at <= at);
+    match previous {
+        Some(checkpoint) if at < config.index_updated_at => index_since(checkpoint, at),
+        _ => index_since(&latest_checkpoint(config), at),
+    }
+}
+
+/// Returns the reward accrued by an NFT from [`Config::claimable_from`] until `at` according to
+/// [`reward_index`], excluding the initial reward, in base units
+pub fn accumulated(config: &Config, at: i64) -> u64 {
+    u64::try_from(reward_index(config, at) / REWARD_INDEX_SCALE).unwrap_or(u64::MAX)
+}
+
+/// Checkpoints [`Config::reward_index`] at `now`. Must be called before the rate changes, so that
+/// the new rate only applies to rewards accrued afterwards. Outside of pool mode, the previous
+/// checkpoint is added to [`Config::index_history`] unless its rate is the same as that of the
+/// latest entry. Fails once the history is full
+pub fn checkpoint(config: &mut Config, now: i64) -> ProgramResult {
+    let reward_index = reward_index(config, now);
+    if config.emission_rate == 0 && config.index_updated_at < now {
+        let previous = latest_checkpoint(config);
+        let is_same_rate = config.index_history.last().is_some_and(|last| {
+            last.claimable_from == previous.claimable_from
+                && last.accrual_ends_at == previous.accrual_ends_at
+                && last.accumulation_duration == previous.accumulation_duration
+                && last.reward_curve == previous.reward_curve
+        });
+        if !is_same_rate {
+            if config.index_history.len() == MAX_INDEX_CHECKPOINTS {
+                msg!(
+                    "[Error] Reward rate can't change more than {} times",
+                    MAX_INDEX_CHECKPOINTS
+                );
+                return Err(GaiminError::InvalidConfig.into());
+            }
+            config.index_history.push(previous);
+        }
+    }
+    config.reward_index = reward_index;
+    config.index_updated_at = now;
+    Ok(())
+}
+
+/// Returns the reward index the NFT last settled at
+fn settled_index(config: &Config, nft_record: &NftRecord) -> u128 {
+    nft_record
+        .reward_index
+        .unwrap_or_else(|| reward_index(config, nft_record.last_claim_at))
+}
+
+/// Returns the reward earned by an NFT from [`NftRecord::last_claim_at`] until `until` in units of
+/// [`EARNED_SCALE`], and the reward index at `until`. Each part is multiplied by the tier of
+/// [`Config::boost_schedule`] reached by the NFT and by the rate of the epoch it accrues in, and
+/// the bonuses of the epochs started within the range are added
+fn earned(config: &Config, epochs: &[Epoch], nft_record: &NftRecord, until: i64) -> (u128, u128) {
+    let last_claim_at = nft_record.last_claim_at;
+    let staked_since = nft_record.staked_since;
+    let settled_index = settled_index(config, nft_record);
+
+    let index_at = |at: i64| {
+        if at <= last_claim_at {
+            settled_index
+        } else {
+            reward_index(config, at).max(settled_index)
+        }
+    };
+
+    // Dates when the boost tier or the epoch changes, splitting the range into parts with a
+    // constant multiplier
+    let mut changes: Vec<i64> = config
+        .boost_schedule
+        .tiers
+        .iter()
+        .map(|tier| staked_since.saturating_add(tier.staked_for))
+        .chain(epochs.iter().map(|epoch| epoch.starts_at))
+        .filter(|&change| change > last_claim_at && change < until)
+        .collect();
+    changes.sort_unstable();
+    changes.dedup();
+
+    let multiplier = |from: i64| {
+        let boost = config
+            .boost_schedule
+            .tiers
+            .iter()
+            .take_while(|tier| staked_since.saturating_add(tier.staked_for) <= from)
+            .last()
+            .map_or(BOOST_BASE, |tier| tier.multiplier_bps);
+        let rate = epochs
+            .iter()
+            .take_while(|epoch| epoch.starts_at <= from)
+            .last()
+            .map_or(EPOCH_RATE_BASE, |epoch| epoch.rate_bps);
+        u128::from(boost) * u128::from(rate)
+    };
+
+    let mut total = 0u128;
+    let mut part_starts_at = last_claim_at;
+    let mut part_start_index = settled_index;
+    for part_ends_at in changes.into_iter().chain([until]) {
+        let part_end_index = index_at(part_ends_at);
+        let accrued = part_end_index.saturating_sub(part_start_index);
+        total = total.saturating_add(accrued.saturating_mul(multiplier(part_starts_at)));
+        part_starts_at = part_ends_at;
+        part_start_index = part_end_index;
+    }
+
+    let total = epochs
+        .iter()
+        .filter(|epoch| epoch.starts_at >= last_claim_at && epoch.starts_at < until)
+        .fold(total, |total, epoch| {
+            total.saturating_add(u128::from(epoch.initial_bonus) * EARNED_SCALE)
+        });
+
+    (total, part_start_index)
+}
+
+/// Returns the reward amount that can still be claimed within [`Config::emission_budget`]
+pub fn remaining_budget(config: &Config) -> u64 {
+    if config.emission_budget == 0 {
+        u64::MAX
+    } else {
+        config.emission_budget.saturating_sub(config.claimed_reward)
+    }
+}
+
+/// Checks whether the NFT can't accrue any more rewards or epoch bonuses. The initial reward can
+/// always be claimed
+pub fn is_accrual_finished(config: &Config, epochs: &[Epoch], nft_record: &NftRecord) -> bool {
+    let accrual_ends_at = accrual_ends_at(config);
+    nft_record.claimed_amount > 0
+        && reward_index(config, accrual_ends_at) <= settled_index(config, nft_record)
+        && !epochs.iter().any(|epoch| {
+            epoch.starts_at >= nft_record.last_claim_at && epoch.starts_at < accrual_ends_at
+        })
+}
+
+/// Computes the reward of claiming at `now` within `epochs`. The total payout doesn't depend on how
+/// often the NFT is claimed while [`NftRecord::staked_since`] and the rate stay the same
+pub fn accrue(config: &Config, epochs: &[Epoch], nft_record: &NftRecord, now: i64) -> Accrual {
+    // Rewards settled on unstaking count as claimed
+    let settled_amount = nft_record
+        .claimed_amount
+        .saturating_add(nft_record.pending_reward);
+    let base_reward = if settled_amount == 0 {
+        config.initial_reward
+    } else {
+        0
+    };
+
+    let accrued_until = i64::max(
+        nft_record.last_claim_at,
+        i64::min(now, accrual_ends_at(config)),
+    );
+    let (earned, reward_index) = earned(config, epochs, nft_record, accrued_until);
+    let earned = earned.saturating_add(nft_record.reward_remainder.into());
+    let accrued = u64::try_from(earned / EARNED_SCALE).unwrap_or(u64::MAX);
+
+    Accrual {
+        reward: u64::min(
+            nft_record.total_amount.saturating_sub(settled_amount),
+            base_reward.saturating_add(accrued),
+        ),
+        last_claim_at: accrued_until,
+        reward_index,
+        reward_remainder: (earned % EARNED_SCALE) as u64,
+    }
+}
@@ -2,41 +2,394 @@ use std::mem;
 
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 use solana_program::{
+    msg,
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack, Sealed},
     pubkey::Pubkey,
 };
 
-use crate::utils::parse_string;
+use crate::{
+    error::GaiminError,
+    instruction::{ConfigChangeArgs, SetEpochScheduleArgs, SetMultisigArgs},
+    processor::DEFAULT_CAMPAIGN_ID,
+    utils::{pack_option, parse_string, unpack_option},
+};
 
 pub const BNB_CHAIN_WALLET_ADDRESS_LENGTH: usize = 40;
 
-/// Stores global configuration options. Created once for the entire program using
+/// Length of the header that starts the current layout of every account owned by the program: a
+/// version byte followed by the [`AccountType`]
+pub const HEADER_LEN: usize = 2;
+
+/// Discriminator stored in the header of an account owned by the program, so that an account of
+/// one type cannot be passed where another is expected
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccountType {
+    Uninitialized,
+    Config,
+    PendingConfigChange,
+    Multisig,
+    NftRecord,
+    ClaimRecord,
+    EpochSchedule,
+    StakeLock,
+}
+
+impl AccountType {
+    /// Determines the type of an account from its data. Version 0 accounts have no header and are
+    /// recognized by their length
+    pub fn of(src: &[u8]) -> AccountType {
+        match src.len() {
+            Config::LEGACY_LEN => AccountType::Config,
+            NftRecord::LEGACY_LEN => AccountType::NftRecord,
+            ClaimRecord::LEGACY_LEN => AccountType::ClaimRecord,
+            _ => [
+                AccountType::Config,
+                AccountType::PendingConfigChange,
+                AccountType::Multisig,
+                AccountType::NftRecord,
+                AccountType::ClaimRecord,
+                AccountType::EpochSchedule,
+                AccountType::StakeLock,
+            ]
+            .into_iter()
+            .find(|&account_type| src.get(1) == Some(&(account_type as u8)))
+            .unwrap_or(AccountType::Uninitialized),
+        }
+    }
+}
+
+/// Layout of an account owned by the program. The layout starts with a header of [`HEADER_LEN`]
+/// bytes. Accounts created by the deployed program before the header was introduced have none.
+/// Those are treated as version 0 and recognized by their length. They can be read directly, but
+/// must be converted with [`crate::instruction::GaiminInstruction::Migrate`] before they can be
+/// written
+pub trait Versioned: Pack {
+    /// Discriminator of the account type
+    const ACCOUNT_TYPE: AccountType;
+
+    /// Version of the current layout
+    const VERSION: u8 = 1;
+
+    /// Length of the version 0 layout. Zero for types introduced later, which have none
+    const LEGACY_LEN: usize = 0;
+
+    /// Reads the version 0 layout
+    fn unpack_legacy(_src: &[u8]) -> Result<Self, ProgramError> {
+        Err(ProgramError::InvalidAccountData)
+    }
+
+    /// Reads an account stored in the current or the version 0 layout
+    fn unpack_versioned(src: &[u8]) -> Result<Self, ProgramError> {
+        if AccountType::of(src) != Self::ACCOUNT_TYPE {
+            return Err(GaiminError::InvalidAccountType.into());
+        }
+
+        if Self::LEGACY_LEN != 0 && src.len() == Self::LEGACY_LEN {
+            Self::unpack_legacy(src)
+        } else if src.len() == Self::LEN {
+            Self::unpack_unchecked(src)
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+
+/// Pauses [`crate::instruction::GaiminInstruction::Nft`]
+pub const PAUSE_REGISTRATION: u8 = 1 << 0;
+/// Pauses [`crate::instruction::GaiminInstruction::CreateClaim`]
+pub const PAUSE_CREATE_CLAIM: u8 = 1 << 1;
+/// Pauses [`crate::instruction::GaiminInstruction::Claim`]
+pub const PAUSE_CLAIM: u8 = 1 << 2;
+/// Pauses [`crate::instruction::GaiminInstruction::Stake`]. Unstaking can't be paused
+pub const PAUSE_STAKE: u8 = 1 << 3;
+pub const PAUSE_ALL: u8 = PAUSE_REGISTRATION | PAUSE_CREATE_CLAIM | PAUSE_CLAIM | PAUSE_STAKE;
+
+/// Maximum number of signers in a [`Multisig`]
+pub const MAX_MULTISIG_SIGNERS: usize = 10;
+
+/// Roles that can be assigned with [`crate::instruction::GaiminInstruction::SetRole`]. The admin
+/// role belongs to [`Config::authority`] and is transferred separately
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Role {
+    /// See [`Config::registrar`]
+    Registrar,
+
+    /// See [`Config::settler`]
+    Settler,
+}
+
+/// Maximum number of tiers of [`RewardCurve::Tiered`]
+pub const MAX_REWARD_TIERS: usize = 4;
+
+/// Schedule by which rewards accrue, evaluated by [`crate::reward::accumulated`]. Durations are
+/// measured from [`Config::claimable_from`] and rates are in base units per
+/// [`Config::accumulation_duration`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum RewardCurve {
+    /// Accrues at a rate of 1
+    #[default]
+    Linear,
+
+    /// Accrues nothing until `cliff` seconds have passed, when the rewards of the cliff are
+    /// released at once. Accrues at a rate of 1 afterwards
+    CliffLinear { cliff: i64 },
+
+    /// Starts at a rate of 1 that halves every `half_life` seconds
+    ExponentialDecay { half_life: i64 },
+
+    /// Accrues at the rate of the latest tier that has started, nothing before the first one.
+    /// Tiers are ordered by their start and there are at most [`MAX_REWARD_TIERS`]
+    Tiered(Vec<RewardTier>),
+}
+
+/// Tier of a [`RewardCurve::Tiered`] schedule
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RewardTier {
+    /// Seconds after [`Config::claimable_from`] when the tier starts
+    pub starts_after: i64,
+
+    /// Base units accrued per [`Config::accumulation_duration`]
+    pub rate: u64,
+}
+
+/// Maximum number of tiers of a [`BoostSchedule`]
+pub const MAX_BOOST_TIERS: usize = 4;
+
+/// Multiplier of 1.0x in basis points
+pub const BOOST_BASE: u16 = 10_000;
+
+/// Multipliers applied to the rewards of NFTs that have been staked continuously, see
+/// [`NftRecord::staked_since`]. Rewards are not boosted before the first tier is reached
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BoostSchedule {
+    /// Tiers ordered by [`BoostTier::staked_for`], at most [`MAX_BOOST_TIERS`]
+    pub tiers: Vec<BoostTier>,
+}
+
+/// Tier of a [`BoostSchedule`]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BoostTier {
+    /// Seconds of continuous staking after which the tier applies
+    pub staked_for: i64,
+
+    /// Multiplier of the rewards accrued within the tier, in basis points of [`BOOST_BASE`]
+    pub multiplier_bps: u16,
+}
+
+/// Fractions of a base unit tracked by [`Config::reward_index`]
+pub const REWARD_INDEX_SCALE: u128 = 1_000_000_000;
+
+/// Maximum number of [`Config::index_history`] entries
+pub const MAX_INDEX_CHECKPOINTS: usize = 16;
+
+/// Value of [`Config::reward_index`] at a past checkpoint and the rate it grew at until the next
+/// one
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IndexCheckpoint {
+    /// Timestamp of the checkpoint
+    pub at: i64,
+
+    /// Reward index at the checkpoint
+    pub index: u128,
+
+    /// [`Config::claimable_from`] until the next checkpoint
+    pub claimable_from: i64,
+
+    /// Date after which rewards stopped accruing until the next checkpoint, see
+    /// [`crate::reward::accrual_ends_at`]
+    pub accrual_ends_at: i64,
+
+    /// [`Config::accumulation_duration`] until the next checkpoint
+    pub accumulation_duration: i64,
+
+    /// [`Config::reward_curve`] until the next checkpoint
+    pub reward_curve: RewardCurve,
+}
+
+/// Maximum number of epochs in an [`EpochSchedule`]
+pub const MAX_EPOCHS: usize = 8;
+
+/// Maximum number of campaigns an NFT can be staked in at the same time, see [`StakeLock`]
+pub const MAX_STAKED_CAMPAIGNS: usize = 8;
+
+/// Epoch rate that keeps [`Config::reward_curve`] unchanged, in basis points
+pub const EPOCH_RATE_BASE: u32 = 10_000;
+
+/// Season of a campaign with its own reward rate, see [`EpochSchedule`]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Epoch {
+    /// Timestamp when the epoch starts. The epoch lasts until the next one starts
+    pub starts_at: i64,
+
+    /// Multiplier of the rewards accrued within the epoch, in basis points of
+    /// [`EPOCH_RATE_BASE`]
+    pub rate_bps: u32,
+
+    /// Reward amount given once to every NFT whose accrual spans the start of the epoch, in base
+    /// units
+    pub initial_bonus: u64,
+}
+
+/// Stores configuration options of a campaign. Created once for each campaign using
 /// [`crate::instruction::GaiminInstruction::Config`]
 ///
 /// Seeds:
 /// 1. Literal `"config"`
+/// 2. Campaign ID, see [`crate::processor::campaign_seed`]
 pub struct Config {
-    /// Account who has the right to issue system instructions
+    /// Identifier of the campaign that namespaces its NFT and claim records
+    pub campaign_id: u64,
+
+    /// Admin account who has the right to update the config and assign roles
     pub authority: Pubkey,
 
+    /// Account proposed as the new authority with
+    /// [`crate::instruction::GaiminInstruction::ProposeAuthority`]. Zeroed if there is no pending
+    /// authority transfer
+    pub pending_authority: Pubkey,
+
     /// Creator of the claimable NFTs
     pub creator: Pubkey,
 
+    /// Account who has the right to register NFTs that were not created by [`Config::creator`]
+    pub registrar: Pubkey,
+
+    /// Account who has the right to close claim records after the rewards have been sent
+    pub settler: Pubkey,
+
     /// Starting date when claiming becomes available
-    pub claimable_from: i32,
+    pub claimable_from: i64,
+
+    /// Date after which claiming is closed
+    pub claimable_until: i64,
+
+    /// Date after which rewards stop accruing. Zero if rewards accrue until
+    /// [`Config::claimable_until`]
+    pub accrual_ends_at: i64,
+
+    /// Reward amount that can be given over time, in base units
+    pub accumulated_reward: u64,
+
+    /// Reward amount given for the first claim, in base units
+    pub initial_reward: u64,
+
+    /// Duration in seconds after which the reward amount of 1 base unit may be claimed
+    pub accumulation_duration: i64,
+
+    /// Duration of a claim record generation in seconds. Always positive
+    pub generation_duration: i64,
+
+    /// Delay in seconds before a scheduled config change can be applied
+    pub timelock_duration: i64,
+
+    /// Number of decimals of the reward token, so that one token equals
+    /// `10^reward_decimals` base units
+    pub reward_decimals: u8,
+
+    /// Bit set of paused operations, see [`PAUSE_REGISTRATION`], [`PAUSE_CREATE_CLAIM`],
+    /// [`PAUSE_CLAIM`] and [`PAUSE_STAKE`]
+    pub paused: u8,
 
-    /// Reward amount that can be given over time
-    pub accumulated_reward: i32,
+    /// Number of NFT records of the campaign. The config can't be closed while any exist. Records
+    /// of version 0 are counted once they are migrated, and those left unmigrated can't be used
+    /// or migrated after the config is closed
+    pub nft_count: u64,
 
-    /// Reward amount given for the first claim
-    pub initial_reward: i32,
+    /// Account who paid for rent exemption and receives the lamports when the config is closed.
+    /// Zeroed for configs migrated from version 0
+    pub payer: Pubkey,
 
-    /// Duration in seconds after which the reward amount of 1 may be claimed
-    pub accumulation_duration: i32,
+    /// Schedule by which rewards accrue. Linear for configs migrated from version 0
+    pub reward_curve: RewardCurve,
 
-    /// Duration of a claim record generation in seconds
-    pub generation_duration: i32,
+    /// Maximum reward amount that can be allocated to NFT records of the campaign, in base units.
+    /// Zero if the budget is unlimited
+    pub emission_budget: u64,
+
+    /// Sum of [`NftRecord::total_amount`] of the registered records, less the unclaimed amounts of
+    /// closed ones. Records migrated from version 0 are counted with their unclaimed amount, and
+    /// those not migrated yet aren't counted
+    pub allocated_reward: u64,
+
+    /// Reward amount added to claim records, in base units. Claims made by the version 0 program
+    /// aren't counted
+    pub claimed_reward: u64,
+
+    /// Boost of continuously staked NFTs. Empty for configs migrated from version 0
+    pub boost_schedule: BoostSchedule,
+
+    /// Reward accrued by an NFT from [`Config::claimable_from`] until [`Config::index_updated_at`],
+    /// in units of [`REWARD_INDEX_SCALE`]. Checkpointed whenever the reward rate changes, so that
+    /// the change only applies to rewards accrued afterwards. In pool mode, it is the reward per
+    /// staked NFT accumulated from the pool
+    pub reward_index: u128,
+
+    /// Timestamp of the last checkpoint of [`Config::reward_index`]. Zero if the rate has never
+    /// changed
+    pub index_updated_at: i64,
+
+    /// Reward emitted per second and shared equally by the staked NFTs, in base units. Non-zero
+    /// in pool mode, where [`Config::reward_curve`] and the allowances of NFT records don't apply.
+    /// Zero for configs migrated from version 0
+    pub emission_rate: u64,
+
+    /// Number of staked NFTs sharing [`Config::emission_rate`] in pool mode
+    pub staked_count: u64,
+
+    /// Checkpoints of [`Config::reward_index`] before the latest one with the rate in force
+    /// since each, oldest first, so that boosts and epochs that change within the range of a claim
+    /// are weighted by the rate of the time. Checkpoints that don't change the rate are merged.
+    /// They are never dropped, so the rate can only change [`MAX_INDEX_CHECKPOINTS`] times. Empty
+    /// in pool mode, where the index is only known since the latest checkpoint
+    pub index_history: Vec<IndexCheckpoint>,
+}
+
+/// Stores a config change scheduled with
+/// [`crate::instruction::GaiminInstruction::ScheduleConfigChange`]. At most one change can be
+/// pending for a campaign
+///
+/// Seeds:
+/// 1. Literal `"config_change"`
+/// 2. Campaign ID, see [`crate::processor::campaign_seed`]
+pub struct PendingConfigChange {
+    /// Account who paid for rent exemption and receives the lamports when the change is applied
+    /// or cancelled
+    pub payer: Pubkey,
+
+    /// Timestamp after which the change can be applied
+    pub effective_at: i64,
+
+    /// Fields of the config to be changed
+    pub change: ConfigChangeArgs,
+}
+
+/// Stores an M-of-N signer set that can be used as the config authority or a role holder instead of
+/// a single key. Created or replaced using [`crate::instruction::GaiminInstruction::SetMultisig`]
+///
+/// Seeds:
+/// 1. Literal `"multisig"`
+/// 2. Campaign ID, see [`crate::processor::campaign_seed`]
+pub struct Multisig {
+    /// Number of signers required to authorize an instruction
+    pub threshold: u8,
+
+    /// Accounts allowed to sign on behalf of the multisig, at most [`MAX_MULTISIG_SIGNERS`]
+    pub signers: Vec<Pubkey>,
+}
+
+/// Stores the epochs of a campaign. Rewards accrue at the rate of the epoch they accrue in, and at
+/// the rate of [`Config::reward_curve`] before the first epoch or if the account doesn't exist.
+/// Payouts remain capped by [`NftRecord::total_amount`]. Created or replaced using
+/// [`crate::instruction::GaiminInstruction::SetEpochSchedule`]
+///
+/// Seeds:
+/// 1. Literal `"epoch_schedule"`
+/// 2. Campaign ID, see [`crate::processor::campaign_seed`]
+pub struct EpochSchedule {
+    /// Epochs ordered by their start, at most [`MAX_EPOCHS`]
+    pub epochs: Vec<Epoch>,
 }
 
 /// Stores staking information about an NFT. Created for each NFT using
@@ -44,17 +397,59 @@ pub struct Config {
 ///
 /// Seeds:
 /// 1. Literal `"nft"`
-/// 2. Mint address of the NFT
+/// 2. Campaign ID, see [`crate::processor::campaign_seed`]
+/// 3. Mint address of the NFT
 pub struct NftRecord {
-    /// The amount that has been claimed
-    pub claimed_amount: i32,
+    /// The amount that has been claimed, in base units
+    pub claimed_amount: u64,
 
     /// Total amount that can be claimed. Equals the sum of [`Config::initial_reward`] and
-    /// [`Config::accumulated_reward`]
-    pub total_amount: i32,
+    /// [`Config::accumulated_reward`], or [`u64::MAX`] in pool mode, where the reward is only
+    /// capped by [`Config::emission_budget`]
+    pub total_amount: u64,
+
+    /// Timestamp up to which rewards have been claimed. Equals [`Config::claimable_from`] or the
+    /// time of staking if no claims have been made since the NFT was staked, and never exceeds
+    /// the date when rewards stop accruing
+    pub last_claim_at: i64,
+
+    /// Account who paid for rent exemption and receives the lamports when the record is closed.
+    /// Zeroed for records migrated from version 0
+    pub payer: Pubkey,
+
+    /// Timestamp since when [`NftRecord::staker`] has staked the NFT, from which rewards accrue
+    /// and [`Config::boost_schedule`] is applied. Zero if the NFT isn't staked
+    pub staked_since: i64,
+
+    /// Wallet that staked the NFT using [`crate::instruction::GaiminInstruction::Stake`]. Kept
+    /// once the NFT is unstaked, so that it can claim [`NftRecord::pending_reward`]
+    pub staker: Pubkey,
 
-    /// Timestamp of the last claim. Zero if no claims have been made
-    pub last_claim_at: i32,
+    /// Value of [`Config::reward_index`] at [`NftRecord::last_claim_at`]. [`None`] for records
+    /// migrated from version 0, whose index is derived from the current rate
+    pub reward_index: Option<u128>,
+
+    /// Earned reward below one base unit carried over to the next claim, in units of
+    /// [`crate::reward::EARNED_SCALE`]
+    pub reward_remainder: u64,
+
+    /// Reward accrued until the NFT was unstaked and not claimed yet, in base units. Paid by the
+    /// next claim of [`NftRecord::staker`], and forfeited if another wallet stakes the NFT
+    pub pending_reward: u64,
+}
+
+/// Stores the campaigns an NFT is staked in. The delegate PDA of the program locks the token as
+/// long as any campaign is listed, so that the NFT can be staked in several campaigns at once.
+/// Created by the first [`crate::instruction::GaiminInstruction::Stake`] and closed by the
+/// [`crate::instruction::GaiminInstruction::Unstake`] that unlocks the token
+///
+/// Seeds:
+/// 1. Literal `"stake_lock"`
+/// 2. Mint address of the NFT
+pub struct StakeLock {
+    /// Campaigns the NFT is staked in, at most [`MAX_STAKED_CAMPAIGNS`]. A campaign stays listed
+    /// if its NFT record is closed while staked, until the NFT is unstaked from it
+    pub campaign_ids: Vec<u64>,
 }
 
 /// Stores information about a claim. Created for each claim using
@@ -65,25 +460,59 @@ pub struct NftRecord {
 ///
 /// Seeds:
 /// 1. Literal `"claim"`
-/// 2. User's wallet account address
-/// 3. Random value
+/// 2. Campaign ID, see [`crate::processor::campaign_seed`]
+/// 3. User's wallet account address
+/// 4. Random value
 pub struct ClaimRecord {
     /// Specifies the time bucket when the record was created
-    pub generation: i32,
+    pub generation: i64,
 
-    /// The reward amount claimed
-    pub amount: i32,
+    /// The reward amount claimed, in base units
+    pub amount: u64,
 
     /// Wallet address of the user who created the record
     pub owner: Pubkey,
 
     /// BNB Chain wallet address where the reward should be sent
     pub bnb_chain_wallet_address: String,
+
+    /// Campaign the record was created for
+    pub campaign_id: u64,
+
+    /// Whether the settler has sent the reward, see
+    /// [`crate::instruction::GaiminInstruction::SettleClaimRecord`]. No reward can be added to a
+    /// settled record, and only settled records can be closed
+    pub settled: bool,
 }
 
+impl Sealed for RewardCurve {}
+impl Sealed for BoostSchedule {}
+impl Sealed for IndexCheckpoint {}
 impl Sealed for Config {}
+impl Sealed for PendingConfigChange {}
+impl Sealed for Multisig {}
 impl Sealed for NftRecord {}
 impl Sealed for ClaimRecord {}
+impl Sealed for EpochSchedule {}
+impl Sealed for StakeLock {}
+
+impl IsInitialized for RewardCurve {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+
+impl IsInitialized for BoostSchedule {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+
+impl IsInitialized for IndexCheckpoint {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
 
 impl IsInitialized for Config {
     fn is_initialized(&self) -> bool {
@@ -91,6 +520,18 @@ impl IsInitialized for Config {
     }
 }
 
+impl IsInitialized for PendingConfigChange {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+
+impl IsInitialized for Multisig {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+
 impl IsInitialized for NftRecord {
     fn is_initialized(&self) -> bool {
         true
@@ -103,140 +544,869 @@ impl IsInitialized for ClaimRecord {
     }
 }
 
+impl IsInitialized for EpochSchedule {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+
+impl IsInitialized for StakeLock {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+
 impl Pack for Config {
-    const LEN: usize = 2 * 32 + 5 * 4;
+    const LEN: usize = HEADER_LEN
+        + 2 * 8
+        + 6 * 32
+        + 8 * 8
+        + 2
+        + RewardCurve::LEN
+        + 3 * 8
+        + BoostSchedule::LEN
+        + 16
+        + 8
+        + 2 * 8
+        + 1
+        + MAX_INDEX_CHECKPOINTS * IndexCheckpoint::LEN;
 
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
         let src = array_ref![src, 0, Config::LEN];
         let (
+            header,
+            campaign_id,
             authority,
+            pending_authority,
             creator,
+            registrar,
+            settler,
             claimable_from,
+            claimable_until,
+            accrual_ends_at,
             total_reward,
             initial_reward,
             reward_period_sec,
             generation_duration,
+            timelock_duration,
+            reward_decimals,
+            paused,
+            nft_count,
+            payer,
+            reward_curve,
+            emission_budget,
+            allocated_reward,
+            claimed_reward,
+            boost_schedule,
+            reward_index,
+            index_updated_at,
+            emission_rate,
+            staked_count,
+            checkpoint_count,
+            index_history,
         ) = array_refs![
             src,
+            HEADER_LEN,
+            mem::size_of::<u64>(),
             mem::size_of::<Pubkey>(),
             mem::size_of::<Pubkey>(),
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>()
+            mem::size_of::<Pubkey>(),
+            mem::size_of::<Pubkey>(),
+            mem::size_of::<Pubkey>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<u8>(),
+            mem::size_of::<u8>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<Pubkey>(),
+            RewardCurve::LEN,
+            mem::size_of::<u64>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<u64>(),
+            BoostSchedule::LEN,
+            mem::size_of::<u128>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<u8>(),
+            MAX_INDEX_CHECKPOINTS * IndexCheckpoint::LEN
         ];
+        assert_header::<Self>(header)?;
+
+        let checkpoint_count = usize::from(checkpoint_count[0]);
+        if checkpoint_count > MAX_INDEX_CHECKPOINTS {
+            msg!(
+                "[Error] Invalid index checkpoint count: {}",
+                checkpoint_count
+            );
+            return Err(ProgramError::InvalidAccountData);
+        }
 
         Ok(Config {
+            campaign_id: u64::from_le_bytes(*campaign_id),
             authority: Pubkey::from(*authority),
+            pending_authority: Pubkey::from(*pending_authority),
             creator: Pubkey::from(*creator),
-            claimable_from: i32::from_le_bytes(*claimable_from),
-            accumulated_reward: i32::from_le_bytes(*total_reward),
-            initial_reward: i32::from_le_bytes(*initial_reward),
-            accumulation_duration: i32::from_le_bytes(*reward_period_sec),
-            generation_duration: i32::from_le_bytes(*generation_duration),
+            registrar: Pubkey::from(*registrar),
+            settler: Pubkey::from(*settler),
+            claimable_from: i64::from_le_bytes(*claimable_from),
+            claimable_until: i64::from_le_bytes(*claimable_until),
+            accrual_ends_at: i64::from_le_bytes(*accrual_ends_at),
+            accumulated_reward: u64::from_le_bytes(*total_reward),
+            initial_reward: u64::from_le_bytes(*initial_reward),
+            accumulation_duration: i64::from_le_bytes(*reward_period_sec),
+            generation_duration: i64::from_le_bytes(*generation_duration),
+            timelock_duration: i64::from_le_bytes(*timelock_duration),
+            reward_decimals: reward_decimals[0],
+            paused: paused[0],
+            nft_count: u64::from_le_bytes(*nft_count),
+            payer: Pubkey::from(*payer),
+            reward_curve: RewardCurve::unpack_from_slice(reward_curve)
+                .map_err(|_| ProgramError::InvalidAccountData)?,
+            emission_budget: u64::from_le_bytes(*emission_budget),
+            allocated_reward: u64::from_le_bytes(*allocated_reward),
+            claimed_reward: u64::from_le_bytes(*claimed_reward),
+            boost_schedule: BoostSchedule::unpack_from_slice(boost_schedule)
+                .map_err(|_| ProgramError::InvalidAccountData)?,
+            reward_index: u128::from_le_bytes(*reward_index),
+            index_updated_at: i64::from_le_bytes(*index_updated_at),
+            emission_rate: u64::from_le_bytes(*emission_rate),
+            staked_count: u64::from_le_bytes(*staked_count),
+            index_history: index_history
+                .chunks_exact(IndexCheckpoint::LEN)
+                .take(checkpoint_count)
+                .map(IndexCheckpoint::unpack_from_slice)
+                .collect::<Result<_, _>>()
+                .map_err(|_| ProgramError::InvalidAccountData)?,
         })
     }
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let dst = array_mut_ref![dst, 0, Config::LEN];
         let (
+            header,
+            campaign_id,
             authority,
+            pending_authority,
             creator,
+            registrar,
+            settler,
             claimable_from,
+            claimable_until,
+            accrual_ends_at,
             total_reward,
             initial_reward,
             reward_period_sec,
             generation_duration,
+            timelock_duration,
+            reward_decimals,
+            paused,
+            nft_count,
+            payer,
+            reward_curve,
+            emission_budget,
+            allocated_reward,
+            claimed_reward,
+            boost_schedule,
+            reward_index,
+            index_updated_at,
+            emission_rate,
+            staked_count,
+            checkpoint_count,
+            index_history,
         ) = mut_array_refs![
             dst,
+            HEADER_LEN,
+            mem::size_of::<u64>(),
             mem::size_of::<Pubkey>(),
             mem::size_of::<Pubkey>(),
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>()
+            mem::size_of::<Pubkey>(),
+            mem::size_of::<Pubkey>(),
+            mem::size_of::<Pubkey>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<u8>(),
+            mem::size_of::<u8>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<Pubkey>(),
+            RewardCurve::LEN,
+            mem::size_of::<u64>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<u64>(),
+            BoostSchedule::LEN,
+            mem::size_of::<u128>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<u8>(),
+            MAX_INDEX_CHECKPOINTS * IndexCheckpoint::LEN
         ];
 
+        *header = current_header::<Self>();
+        *campaign_id = self.campaign_id.to_le_bytes();
         authority.copy_from_slice(&self.authority.to_bytes());
+        pending_authority.copy_from_slice(&self.pending_authority.to_bytes());
         creator.copy_from_slice(&self.creator.to_bytes());
+        registrar.copy_from_slice(&self.registrar.to_bytes());
+        settler.copy_from_slice(&self.settler.to_bytes());
         *claimable_from = self.claimable_from.to_le_bytes();
+        *claimable_until = self.claimable_until.to_le_bytes();
+        *accrual_ends_at = self.accrual_ends_at.to_le_bytes();
         *total_reward = self.accumulated_reward.to_le_bytes();
         *initial_reward = self.initial_reward.to_le_bytes();
         *reward_period_sec = self.accumulation_duration.to_le_bytes();
         *generation_duration = self.generation_duration.to_le_bytes();
+        *timelock_duration = self.timelock_duration.to_le_bytes();
+        reward_decimals[0] = self.reward_decimals;
+        paused[0] = self.paused;
+        *nft_count = self.nft_count.to_le_bytes();
+        payer.copy_from_slice(&self.payer.to_bytes());
+        self.reward_curve.pack_into_slice(reward_curve);
+        *emission_budget = self.emission_budget.to_le_bytes();
+        *allocated_reward = self.allocated_reward.to_le_bytes();
+        *claimed_reward = self.claimed_reward.to_le_bytes();
+        self.boost_schedule.pack_into_slice(boost_schedule);
+        *reward_index = self.reward_index.to_le_bytes();
+        *index_updated_at = self.index_updated_at.to_le_bytes();
+        *emission_rate = self.emission_rate.to_le_bytes();
+        *staked_count = self.staked_count.to_le_bytes();
+        checkpoint_count[0] = self.index_history.len() as u8;
+        index_history.fill(0);
+        for (dst, checkpoint) in index_history
+            .chunks_exact_mut(IndexCheckpoint::LEN)
+            .zip(&self.index_history)
+        {
+            checkpoint.pack_into_slice(dst);
+        }
+    }
+}
+
+impl Pack for IndexCheckpoint {
+    const LEN: usize = 8 + 16 + 3 * 8 + RewardCurve::LEN;
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, IndexCheckpoint::LEN];
+        let (at, index, claimable_from, accrual_ends_at, accumulation_duration, reward_curve) = array_refs![
+            src,
+            mem::size_of::<i64>(),
+            mem::size_of::<u128>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            RewardCurve::LEN
+        ];
+
+        Ok(IndexCheckpoint {
+            at: i64::from_le_bytes(*at),
+            index: u128::from_le_bytes(*index),
+            claimable_from: i64::from_le_bytes(*claimable_from),
+            accrual_ends_at: i64::from_le_bytes(*accrual_ends_at),
+            accumulation_duration: i64::from_le_bytes(*accumulation_duration),
+            reward_curve: RewardCurve::unpack_from_slice(reward_curve)?,
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, IndexCheckpoint::LEN];
+        let (at, index, claimable_from, accrual_ends_at, accumulation_duration, reward_curve) = mut_array_refs![
+            dst,
+            mem::size_of::<i64>(),
+            mem::size_of::<u128>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<i64>(),
+            RewardCurve::LEN
+        ];
+
+        *at = self.at.to_le_bytes();
+        *index = self.index.to_le_bytes();
+        *claimable_from = self.claimable_from.to_le_bytes();
+        *accrual_ends_at = self.accrual_ends_at.to_le_bytes();
+        *accumulation_duration = self.accumulation_duration.to_le_bytes();
+        self.reward_curve.pack_into_slice(reward_curve);
+    }
+}
+
+impl Pack for BoostSchedule {
+    const LEN: usize = 1 + MAX_BOOST_TIERS * (8 + 2);
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, BoostSchedule::LEN];
+        let (tier_count, tiers) = array_refs![src, 1, MAX_BOOST_TIERS * (8 + 2)];
+        if usize::from(tier_count[0]) > MAX_BOOST_TIERS {
+            msg!("[Error] Invalid boost tier count: {}", tier_count[0]);
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(BoostSchedule {
+            tiers: tiers
+                .chunks_exact(8 + 2)
+                .take(tier_count[0].into())
+                .map(|tier| {
+                    let (staked_for, multiplier_bps) =
+                        array_refs![array_ref![tier, 0, 8 + 2], 8, 2];
+                    BoostTier {
+                        staked_for: i64::from_le_bytes(*staked_for),
+                        multiplier_bps: u16::from_le_bytes(*multiplier_bps),
+                    }
+                })
+                .collect(),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, BoostSchedule::LEN];
+        let (tier_count, tiers) = mut_array_refs![dst, 1, MAX_BOOST_TIERS * (8 + 2)];
+
+        tier_count[0] = self.tiers.len() as u8;
+        tiers.fill(0);
+        for (tier, dst) in self.tiers.iter().zip(tiers.chunks_exact_mut(8 + 2)) {
+            let (staked_for, multiplier_bps) = mut_array_refs![array_mut_ref![dst, 0, 8 + 2], 8, 2];
+            *staked_for = tier.staked_for.to_le_bytes();
+            *multiplier_bps = tier.multiplier_bps.to_le_bytes();
+        }
+    }
+}
+
+impl Pack for RewardCurve {
+    const LEN: usize = 1 + 8 + 1 + MAX_REWARD_TIERS * (8 + 8);
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, RewardCurve::LEN];
+        let (kind, parameter, tier_count, tiers) = array_refs![
+            src,
+            mem::size_of::<u8>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<u8>(),
+            MAX_REWARD_TIERS * (8 + 8)
+        ];
+        let parameter = i64::from_le_bytes(*parameter);
+
+        match kind[0] {
+            0 => Ok(RewardCurve::Linear),
+            1 => Ok(RewardCurve::CliffLinear { cliff: parameter }),
+            2 => Ok(RewardCurve::ExponentialDecay {
+                half_life: parameter,
+            }),
+            3 if usize::from(tier_count[0]) <= MAX_REWARD_TIERS => Ok(RewardCurve::Tiered(
+                tiers
+                    .chunks_exact(8 + 8)
+                    .take(tier_count[0].into())
+                    .map(|tier| {
+                        let (starts_after, rate) = array_refs![array_ref![tier, 0, 8 + 8], 8, 8];
+                        RewardTier {
+                            starts_after: i64::from_le_bytes(*starts_after),
+                            rate: u64::from_le_bytes(*rate),
+                        }
+                    })
+                    .collect(),
+            )),
+            _ => {
+                msg!("[Error] Invalid reward curve");
+                Err(ProgramError::InvalidInstructionData)
+            }
+        }
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, RewardCurve::LEN];
+        let (kind, parameter, tier_count, tiers) = mut_array_refs![
+            dst,
+            mem::size_of::<u8>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<u8>(),
+            MAX_REWARD_TIERS * (8 + 8)
+        ];
+        tier_count[0] = 0;
+        tiers.fill(0);
+
+        match self {
+            RewardCurve::Linear => {
+                kind[0] = 0;
+                *parameter = [0; 8];
+            }
+            RewardCurve::CliffLinear { cliff } => {
+                kind[0] = 1;
+                *parameter = cliff.to_le_bytes();
+            }
+            RewardCurve::ExponentialDecay { half_life } => {
+                kind[0] = 2;
+                *parameter = half_life.to_le_bytes();
+            }
+            RewardCurve::Tiered(rates) => {
+                kind[0] = 3;
+                *parameter = [0; 8];
+                tier_count[0] = rates.len() as u8;
+                for (tier, dst) in rates.iter().zip(tiers.chunks_exact_mut(8 + 8)) {
+                    let (starts_after, rate) = mut_array_refs![array_mut_ref![dst, 0, 8 + 8], 8, 8];
+                    *starts_after = tier.starts_after.to_le_bytes();
+                    *rate = tier.rate.to_le_bytes();
+                }
+            }
+        }
+    }
+}
+
+impl Pack for PendingConfigChange {
+    const LEN: usize = HEADER_LEN + 32 + 8 + ConfigChangeArgs::LEN;
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, PendingConfigChange::LEN];
+        let (header, payer, effective_at, change) = array_refs![
+            src,
+            HEADER_LEN,
+            mem::size_of::<Pubkey>(),
+            mem::size_of::<i64>(),
+            ConfigChangeArgs::LEN
+        ];
+        assert_header::<Self>(header)?;
+
+        Ok(PendingConfigChange {
+            payer: Pubkey::from(*payer),
+            effective_at: i64::from_le_bytes(*effective_at),
+            change: ConfigChangeArgs::unpack_from_slice(change)?,
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, PendingConfigChange::LEN];
+        let (header, payer, effective_at, change) = mut_array_refs![
+            dst,
+            HEADER_LEN,
+            mem::size_of::<Pubkey>(),
+            mem::size_of::<i64>(),
+            ConfigChangeArgs::LEN
+        ];
+
+        *header = current_header::<Self>();
+        payer.copy_from_slice(&self.payer.to_bytes());
+        *effective_at = self.effective_at.to_le_bytes();
+        self.change.pack_into_slice(change);
+    }
+}
+
+impl Pack for Multisig {
+    const LEN: usize = HEADER_LEN + SetMultisigArgs::LEN;
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, Multisig::LEN];
+        let (header, signer_set) = array_refs![src, HEADER_LEN, SetMultisigArgs::LEN];
+        assert_header::<Self>(header)?;
+
+        let signer_set = SetMultisigArgs::unpack_from_slice(signer_set)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        Ok(Multisig {
+            threshold: signer_set.threshold,
+            signers: signer_set.signers,
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, Multisig::LEN];
+        let (header, signer_set) = mut_array_refs![dst, HEADER_LEN, SetMultisigArgs::LEN];
+
+        *header = current_header::<Self>();
+        SetMultisigArgs {
+            threshold: self.threshold,
+            signers: self.signers.clone(),
+        }
+        .pack_into_slice(signer_set);
+    }
+}
+
+impl Pack for EpochSchedule {
+    const LEN: usize = HEADER_LEN + SetEpochScheduleArgs::LEN;
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, EpochSchedule::LEN];
+        let (header, epochs) = array_refs![src, HEADER_LEN, SetEpochScheduleArgs::LEN];
+        assert_header::<Self>(header)?;
+
+        let epochs = SetEpochScheduleArgs::unpack_from_slice(epochs)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        Ok(EpochSchedule {
+            epochs: epochs.epochs,
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, EpochSchedule::LEN];
+        let (header, epochs) = mut_array_refs![dst, HEADER_LEN, SetEpochScheduleArgs::LEN];
+
+        *header = current_header::<Self>();
+        SetEpochScheduleArgs {
+            epochs: self.epochs.clone(),
+        }
+        .pack_into_slice(epochs);
+    }
+}
+
+impl Pack for StakeLock {
+    const LEN: usize = HEADER_LEN + 1 + MAX_STAKED_CAMPAIGNS * 8;
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, StakeLock::LEN];
+        let (header, campaign_count, campaign_ids) = array_refs![
+            src,
+            HEADER_LEN,
+            mem::size_of::<u8>(),
+            MAX_STAKED_CAMPAIGNS * 8
+        ];
+        assert_header::<Self>(header)?;
+
+        let campaign_count = usize::from(campaign_count[0]);
+        if campaign_count > MAX_STAKED_CAMPAIGNS {
+            msg!("[Error] Invalid staked campaign count: {}", campaign_count);
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(StakeLock {
+            campaign_ids: campaign_ids
+                .chunks_exact(8)
+                .take(campaign_count)
+                .map(|campaign_id| u64::from_le_bytes(campaign_id.try_into().unwrap()))
+                .collect(),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, StakeLock::LEN];
+        let (header, campaign_count, campaign_ids) = mut_array_refs![
+            dst,
+            HEADER_LEN,
+            mem::size_of::<u8>(),
+            MAX_STAKED_CAMPAIGNS * 8
+        ];
+
+        *header = current_header::<Self>();
+        campaign_count[0] = self.campaign_ids.len() as u8;
+        campaign_ids.fill(0);
+        for (dst, campaign_id) in campaign_ids.chunks_exact_mut(8).zip(&self.campaign_ids) {
+            dst.copy_from_slice(&campaign_id.to_le_bytes());
+        }
     }
 }
 
 impl Pack for NftRecord {
-    const LEN: usize = 3 * 4;
+    const LEN: usize = HEADER_LEN + 3 * 8 + 32 + 8 + 32 + (1 + 16) + 2 * 8;
 
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
         let src = array_ref![src, 0, NftRecord::LEN];
-        let (claimed_amount, total_amount, last_claim_at) = array_refs![
+        let (
+            header,
+            claimed_amount,
+            total_amount,
+            last_claim_at,
+            payer,
+            staked_since,
+            staker,
+            reward_index_flag,
+            reward_index,
+            reward_remainder,
+            pending_reward,
+        ) = array_refs![
             src,
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>()
+            HEADER_LEN,
+            mem::size_of::<u64>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<Pubkey>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<Pubkey>(),
+            1,
+            mem::size_of::<u128>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<u64>()
         ];
+        assert_header::<Self>(header)?;
 
         Ok(NftRecord {
-            claimed_amount: i32::from_le_bytes(*claimed_amount),
-            total_amount: i32::from_le_bytes(*total_amount),
-            last_claim_at: i32::from_le_bytes(*last_claim_at),
+            claimed_amount: u64::from_le_bytes(*claimed_amount),
+            total_amount: u64::from_le_bytes(*total_amount),
+            last_claim_at: i64::from_le_bytes(*last_claim_at),
+            payer: Pubkey::from(*payer),
+            staked_since: i64::from_le_bytes(*staked_since),
+            staker: Pubkey::from(*staker),
+            reward_index: unpack_option(reward_index_flag[0], u128::from_le_bytes(*reward_index))
+                .map_err(|_| ProgramError::InvalidAccountData)?,
+            reward_remainder: u64::from_le_bytes(*reward_remainder),
+            pending_reward: u64::from_le_bytes(*pending_reward),
         })
     }
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let dst = array_mut_ref![dst, 0, NftRecord::LEN];
-        let (claimed_amount, total_amount, last_claim_at) = mut_array_refs![
+        let (
+            header,
+            claimed_amount,
+            total_amount,
+            last_claim_at,
+            payer,
+            staked_since,
+            staker,
+            reward_index_flag,
+            reward_index,
+            reward_remainder,
+            pending_reward,
+        ) = mut_array_refs![
             dst,
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>()
+            HEADER_LEN,
+            mem::size_of::<u64>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<Pubkey>(),
+            mem::size_of::<i64>(),
+            mem::size_of::<Pubkey>(),
+            1,
+            mem::size_of::<u128>(),
+            mem::size_of::<u64>(),
+            mem::size_of::<u64>()
         ];
 
+        *header = current_header::<Self>();
         *claimed_amount = self.claimed_amount.to_le_bytes();
         *total_amount = self.total_amount.to_le_bytes();
         *last_claim_at = self.last_claim_at.to_le_bytes();
+        payer.copy_from_slice(&self.payer.to_bytes());
+        *staked_since = self.staked_since.to_le_bytes();
+        staker.copy_from_slice(&self.staker.to_bytes());
+        pack_option(
+            self.reward_index.map(u128::to_le_bytes),
+            reward_index_flag,
+            reward_index,
+        );
+        *reward_remainder = self.reward_remainder.to_le_bytes();
+        *pending_reward = self.pending_reward.to_le_bytes();
     }
 }
 
 impl Pack for ClaimRecord {
-    const LEN: usize = 2 * 4 + 32 + BNB_CHAIN_WALLET_ADDRESS_LENGTH;
+    const LEN: usize = HEADER_LEN + 2 * 8 + 32 + BNB_CHAIN_WALLET_ADDRESS_LENGTH + 8 + 1;
 
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
         let src = array_ref![src, 0, ClaimRecord::LEN];
-        let (generation, amount, owner, bnb_chain_wallet_address) = array_refs![
+        let (header, generation, amount, owner, bnb_chain_wallet_address, campaign_id, settled) = array_refs![
             src,
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>(),
+            HEADER_LEN,
+            mem::size_of::<i64>(),
+            mem::size_of::<u64>(),
             mem::size_of::<Pubkey>(),
-            BNB_CHAIN_WALLET_ADDRESS_LENGTH
+            BNB_CHAIN_WALLET_ADDRESS_LENGTH,
+            mem::size_of::<u64>(),
+            mem::size_of::<u8>()
         ];
+        assert_header::<Self>(header)?;
 
         Ok(ClaimRecord {
-            generation: i32::from_le_bytes(*generation),
-            amount: i32::from_le_bytes(*amount),
+            generation: i64::from_le_bytes(*generation),
+            amount: u64::from_le_bytes(*amount),
             owner: Pubkey::from(*owner),
             bnb_chain_wallet_address: parse_string(bnb_chain_wallet_address)?,
+            campaign_id: u64::from_le_bytes(*campaign_id),
+            settled: settled[0] != 0,
         })
     }
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let dst = array_mut_ref![dst, 0, ClaimRecord::LEN];
-        let (generation, claimed_amount, owner, bnb_chain_wallet_address) = mut_array_refs![
+        let (
+            header,
+            generation,
+            claimed_amount,
+            owner,
+            bnb_chain_wallet_address,
+            campaign_id,
+            settled,
+        ) = mut_array_refs![
             dst,
-            mem::size_of::<i32>(),
-            mem::size_of::<i32>(),
+            HEADER_LEN,
+            mem::size_of::<i64>(),
+            mem::size_of::<u64>(),
             mem::size_of::<Pubkey>(),
-            BNB_CHAIN_WALLET_ADDRESS_LENGTH
+            BNB_CHAIN_WALLET_ADDRESS_LENGTH,
+            mem::size_of::<u64>(),
+            mem::size_of::<u8>()
         ];
 
+        *header = current_header::<Self>();
         *generation = self.generation.to_le_bytes();
         *claimed_amount = self.amount.to_le_bytes();
         owner.copy_from_slice(&self.owner.to_bytes());
-        bnb_chain_wallet_address.copy_from_slice(&self.bnb_chain_wallet_address.as_bytes());
+        bnb_chain_wallet_address.copy_from_slice(self.bnb_chain_wallet_address.as_bytes());
+        *campaign_id = self.campaign_id.to_le_bytes();
+        settled[0] = self.settled as u8;
+    }
+}
+
+// The version 0 layout is the one deployed before campaigns, which stored a single config with
+// times and amounts as `i32`
+impl Versioned for Config {
+    const ACCOUNT_TYPE: AccountType = AccountType::Config;
+    const LEGACY_LEN: usize = 2 * 32 + 5 * 4;
+
+    fn unpack_legacy(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() != Self::LEGACY_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let src = array_ref![src, 0, Config::LEGACY_LEN];
+        let (
+            authority,
+            creator,
+            claimable_from,
+            total_reward,
+            initial_reward,
+            reward_period_sec,
+            generation_duration,
+        ) = array_refs![
+            src,
+            mem::size_of::<Pubkey>(),
+            mem::size_of::<Pubkey>(),
+            mem::size_of::<i32>(),
+            mem::size_of::<i32>(),
+            mem::size_of::<i32>(),
+            mem::size_of::<i32>(),
+            mem::size_of::<i32>()
+        ];
+
+        // The deployed program had a single campaign without a claiming deadline, roles or a
+        // timelock
+        Ok(Config {
+            campaign_id: DEFAULT_CAMPAIGN_ID,
+            authority: Pubkey::from(*authority),
+            pending_authority: Pubkey::default(),
+            creator: Pubkey::from(*creator),
+            registrar: Pubkey::default(),
+            settler: Pubkey::default(),
+            claimable_from: i32::from_le_bytes(*claimable_from).into(),
+            claimable_until: i64::MAX,
+            accrual_ends_at: 0,
+            accumulated_reward: legacy_amount(*total_reward)?,
+            initial_reward: legacy_amount(*initial_reward)?,
+            accumulation_duration: i32::from_le_bytes(*reward_period_sec).into(),
+            generation_duration: i32::from_le_bytes(*generation_duration).into(),
+            timelock_duration: 0,
+            reward_decimals: 0,
+            paused: 0,
+            nft_count: 0,
+            payer: Pubkey::default(),
+            reward_curve: RewardCurve::Linear,
+            emission_budget: 0,
+            allocated_reward: 0,
+            claimed_reward: 0,
+            boost_schedule: BoostSchedule::default(),
+            reward_index: 0,
+            index_updated_at: 0,
+            emission_rate: 0,
+            staked_count: 0,
+            index_history: vec![],
+        })
     }
 }
+
+impl Versioned for NftRecord {
+    const ACCOUNT_TYPE: AccountType = AccountType::NftRecord;
+    const LEGACY_LEN: usize = 3 * 4;
+
+    fn unpack_legacy(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() != Self::LEGACY_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let src = array_ref![src, 0, NftRecord::LEGACY_LEN];
+        let (claimed_amount, total_amount, last_claim_at) = array_refs![
+            src,
+            mem::size_of::<i32>(),
+            mem::size_of::<i32>(),
+            mem::size_of::<i32>()
+        ];
+
+        Ok(NftRecord {
+            claimed_amount: legacy_amount(*claimed_amount)?,
+            total_amount: legacy_amount(*total_amount)?,
+            last_claim_at: i32::from_le_bytes(*last_claim_at).into(),
+            payer: Pubkey::default(),
+            staked_since: 0,
+            staker: Pubkey::default(),
+            reward_index: None,
+            reward_remainder: 0,
+            pending_reward: 0,
+        })
+    }
+}
+
+impl Versioned for ClaimRecord {
+    const ACCOUNT_TYPE: AccountType = AccountType::ClaimRecord;
+    const LEGACY_LEN: usize = 2 * 4 + 32 + BNB_CHAIN_WALLET_ADDRESS_LENGTH;
+
+    fn unpack_legacy(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() != Self::LEGACY_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let src = array_ref![src, 0, ClaimRecord::LEGACY_LEN];
+        let (generation, amount, owner, bnb_chain_wallet_address) = array_refs![
+            src,
+            mem::size_of::<i32>(),
+            mem::size_of::<i32>(),
+            mem::size_of::<Pubkey>(),
+            BNB_CHAIN_WALLET_ADDRESS_LENGTH
+        ];
+
+        // Records of the deployed program belong to the single campaign it had
+        Ok(ClaimRecord {
+            generation: i32::from_le_bytes(*generation).into(),
+            amount: legacy_amount(*amount)?,
+            owner: Pubkey::from(*owner),
+            bnb_chain_wallet_address: parse_string(bnb_chain_wallet_address)?,
+            campaign_id: DEFAULT_CAMPAIGN_ID,
+            settled: false,
+        })
+    }
+}
+
+// Types introduced after the deployed program have no version 0 layout
+impl Versioned for PendingConfigChange {
+    const ACCOUNT_TYPE: AccountType = AccountType::PendingConfigChange;
+}
+
+impl Versioned for Multisig {
+    const ACCOUNT_TYPE: AccountType = AccountType::Multisig;
+}
+
+impl Versioned for EpochSchedule {
+    const ACCOUNT_TYPE: AccountType = AccountType::EpochSchedule;
+}
+
+impl Versioned for StakeLock {
+    const ACCOUNT_TYPE: AccountType = AccountType::StakeLock;
+}
+
+fn current_header<T: Versioned>() -> [u8; HEADER_LEN] {
+    [T::VERSION, T::ACCOUNT_TYPE as u8]
+}
+
+fn assert_header<T: Versioned>(header: &[u8; HEADER_LEN]) -> Result<(), ProgramError> {
+    if header[1] != T::ACCOUNT_TYPE as u8 {
+        return Err(GaiminError::InvalidAccountType.into());
+    }
+    if header[0] != T::VERSION {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+fn legacy_amount(bytes: [u8; 4]) -> Result<u64, ProgramError> {
+    u64::try_from(i32::from_le_bytes(bytes)).map_err(|_| ProgramError::InvalidAccountData)
+}
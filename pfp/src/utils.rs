@@ -1,9 +1,97 @@
+use std::{
+    ops::{Deref, DerefMut},
+    slice,
+};
+
 use solana_program::{
-    account_info::AccountInfo, entrypoint::ProgramResult, instruction::Instruction, msg,
-    program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, rent::Rent,
+    account_info::AccountInfo,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    program_utils::limited_deserialize,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
 };
 
-use crate::error::GaiminError;
+use crate::{
+    error::GaiminError,
+    state::{AccountType, Multisig, Versioned, MAX_MULTISIG_SIGNERS},
+};
+
+/// State of a program account that has been checked to be initialized, owned by the program and
+/// hold a `T`. Modifications are written back to the account with [`Loaded::save`]
+pub struct Loaded<'a, 'info, T: Versioned> {
+    pub account: &'a AccountInfo<'info>,
+    state: T,
+}
+
+impl<'a, 'info, T: Versioned> Loaded<'a, 'info, T> {
+    /// Loads the account at the PDA derived from `seeds`
+    pub fn load(
+        account: &'a AccountInfo<'info>,
+        program_id: &Pubkey,
+        seeds: &[&[u8]],
+    ) -> Result<Self, ProgramError> {
+        assert_derived_from(account, program_id, seeds)?;
+        Self::load_unverified_address(account, program_id)
+    }
+
+    /// Loads the account at the PDA derived from `seeds_with_bump`
+    pub fn load_with_bump(
+        account: &'a AccountInfo<'info>,
+        program_id: &Pubkey,
+        seeds_with_bump: &[&[u8]],
+    ) -> Result<Self, ProgramError> {
+        assert_derived_from_with_bump(account, program_id, seeds_with_bump)?;
+        Self::load_unverified_address(account, program_id)
+    }
+
+    /// Loads the account without checking its address. Used when the seeds are unknown until the
+    /// state is read, in which case the caller must validate the address against the state
+    pub fn load_unverified_address(
+        account: &'a AccountInfo<'info>,
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        assert_owned_by(account, program_id)?;
+        assert_initialized(account, program_id)?;
+        let state = T::unpack_versioned(&account.try_borrow_data()?)?;
+
+        Ok(Self { account, state })
+    }
+
+    /// Writes the state back to the account. Accounts in an older layout must be migrated first
+    pub fn save(&self) -> ProgramResult {
+        let mut data = self.account.try_borrow_mut_data()?;
+        if data.len() != T::LEN {
+            msg!(
+                "[Error] Account {} must be migrated first",
+                self.account.key
+            );
+            return Err(ProgramError::InvalidAccountData);
+        }
+        self.state.pack_into_slice(&mut data);
+
+        Ok(())
+    }
+}
+
+impl<T: Versioned> Deref for Loaded<'_, '_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.state
+    }
+}
+
+impl<T: Versioned> DerefMut for Loaded<'_, '_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.state
+    }
+}
 
 pub fn assert_signer(acc: &AccountInfo) -> ProgramResult {
     if !acc.is_signer {
@@ -14,6 +102,15 @@ pub fn assert_signer(acc: &AccountInfo) -> ProgramResult {
     }
 }
 
+pub fn assert_owned_by(acc: &AccountInfo, owner: &Pubkey) -> ProgramResult {
+    if acc.owner != owner {
+        msg!("[Error] Account {} is not owned by {}", acc.key, owner);
+        Err(ProgramError::IncorrectProgramId)
+    } else {
+        Ok(())
+    }
+}
+
 pub fn assert_derived_from(
     acc: &AccountInfo,
     program_id: &Pubkey,
@@ -51,22 +148,99 @@ pub fn assert_derived_from_with_bump(
         })
 }
 
-pub fn assert_initialized(acc: &AccountInfo) -> ProgramResult {
-    if is_initialized(acc)? {
+pub fn assert_initialized(acc: &AccountInfo, program_id: &Pubkey) -> ProgramResult {
+    if is_initialized(acc, program_id)? {
         Ok(())
     } else {
         Err(ProgramError::UninitializedAccount)
     }
 }
 
-pub fn assert_uninitialized(acc: &AccountInfo) -> ProgramResult {
-    if is_initialized(acc)? {
+pub fn assert_uninitialized(acc: &AccountInfo, program_id: &Pubkey) -> ProgramResult {
+    if is_initialized(acc, program_id)? {
         Err(ProgramError::AccountAlreadyInitialized)
     } else {
         Ok(())
     }
 }
 
+pub fn assert_upgrade_authority(
+    program_data: &AccountInfo,
+    program_id: &Pubkey,
+    authority: &Pubkey,
+) -> ProgramResult {
+    assert_derived_from(
+        program_data,
+        &bpf_loader_upgradeable::id(),
+        &[&program_id.to_bytes()],
+    )?;
+    if *program_data.owner != bpf_loader_upgradeable::id() {
+        msg!("[Error] Program data account is not owned by the upgradeable loader");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let state: UpgradeableLoaderState = limited_deserialize(
+        &program_data.try_borrow_data()?,
+        UpgradeableLoaderState::size_of_programdata_metadata() as u64,
+    )
+    .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    match state {
+        UpgradeableLoaderState::ProgramData {
+            upgrade_authority_address: Some(upgrade_authority),
+            ..
+        } if upgrade_authority == *authority => Ok(()),
+        _ => {
+            msg!(
+                "[Error] {} is not the upgrade authority of the program",
+                authority
+            );
+            Err(GaiminError::NotUpgradeAuthority.into())
+        }
+    }
+}
+
+/// Checks that `authority` is the `expected` account and that it authorized the instruction. A
+/// multisig authorizes it when at least [`Multisig::threshold`] of its signers are passed in
+/// `signers` and have signed the transaction. Any other account must sign the transaction itself
+pub fn assert_authority(
+    program_id: &Pubkey,
+    expected: &Pubkey,
+    authority: &AccountInfo,
+    signers: &[AccountInfo],
+) -> ProgramResult {
+    if expected != authority.key {
+        msg!("[Error] {} is not authorized", authority.key);
+        return Err(GaiminError::PermissionDenied.into());
+    }
+
+    if authority.owner != program_id
+        || AccountType::of(&authority.try_borrow_data()?) != AccountType::Multisig
+    {
+        return assert_signer(authority);
+    }
+
+    let multisig = Loaded::<Multisig>::load_unverified_address(authority, program_id)?;
+    let mut matched = [false; MAX_MULTISIG_SIGNERS];
+    for signer in signers.iter().filter(|signer| signer.is_signer) {
+        if let Some(position) = multisig.signers.iter().position(|key| key == signer.key) {
+            matched[position] = true;
+        }
+    }
+
+    let signed = matched.iter().filter(|&&matched| matched).count();
+    if signed < multisig.threshold as usize {
+        msg!(
+            "[Error] Multisig requires {} signers, received {}",
+            multisig.threshold,
+            signed
+        );
+        return Err(GaiminError::PermissionDenied.into());
+    }
+
+    Ok(())
+}
+
 pub fn assert_ix_data_length(data: &[u8], len: usize) -> ProgramResult {
     if data.len() != len {
         msg!(
@@ -80,32 +254,121 @@ pub fn assert_ix_data_length(data: &[u8], len: usize) -> ProgramResult {
     }
 }
 
-pub fn is_initialized(acc: &AccountInfo) -> Result<bool, ProgramError> {
-    acc.try_borrow_lamports().map(|lamports| **lamports != 0)
+/// Checks whether the account holds program data. Lamports alone don't make an account
+/// initialized, since anyone can transfer them to any address
+pub fn is_initialized(acc: &AccountInfo, program_id: &Pubkey) -> Result<bool, ProgramError> {
+    if acc.owner != program_id {
+        return Ok(false);
+    }
+
+    let data = acc.try_borrow_data()?;
+    Ok(AccountType::of(&data) != AccountType::Uninitialized)
 }
 
-pub fn create_account_ix<T: Pack>(acc: &Pubkey, payer: &Pubkey, owner: &Pubkey) -> Instruction {
-    solana_program::system_instruction::create_account(
-        &payer,
-        &acc,
-        Rent::default().minimum_balance(T::LEN),
-        T::LEN as u64,
-        owner,
+/// Creates a rent-exempt program account of `T::LEN` bytes at a PDA signed with
+/// `seeds_with_bump`. Unlike `create_account`, this succeeds if the address already holds
+/// lamports, so prefunding a PDA can't block its creation
+pub fn create_pda_account<'a, T: Pack>(
+    acc: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    program_id: &Pubkey,
+    seeds_with_bump: &[&[u8]],
+) -> ProgramResult {
+    let lamports = Rent::default()
+        .minimum_balance(T::LEN)
+        .saturating_sub(acc.lamports());
+    if lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, acc.key, lamports),
+            &[payer.clone(), acc.clone()],
+        )?;
+    }
+
+    invoke_signed(
+        &system_instruction::allocate(acc.key, T::LEN as u64),
+        slice::from_ref(acc),
+        &[seeds_with_bump],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(acc.key, program_id),
+        slice::from_ref(acc),
+        &[seeds_with_bump],
     )
 }
 
-pub fn delete_account(acc: &AccountInfo, dest: &AccountInfo) -> ProgramResult {
+/// Resizes a program account to `T::LEN`, transferring the missing rent exemption from `payer`
+pub fn realloc_account<'a, T: Pack>(
+    acc: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+) -> ProgramResult {
+    let lamports = Rent::default()
+        .minimum_balance(T::LEN)
+        .saturating_sub(acc.lamports());
+    if lamports > 0 {
+        assert_signer(payer)?;
+        invoke(
+            &system_instruction::transfer(payer.key, acc.key, lamports),
+            &[payer.clone(), acc.clone()],
+        )?;
+    }
+
+    acc.realloc(T::LEN, false)
+}
+
+/// Rewrites a program account stored in an older layout to the current one, resizing it in place
+pub fn migrate_account<'a, T: Versioned>(
+    acc: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+) -> ProgramResult {
+    if acc.data_len() == T::LEN {
+        msg!("[Error] Account already uses the current layout");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let state = T::unpack_versioned(&acc.try_borrow_data()?)?;
+    realloc_account::<T>(acc, payer)?;
+    T::pack(state, &mut acc.try_borrow_mut_data()?)
+}
+
+/// Closes a program account, sending its lamports to `dest`. The account is emptied and handed
+/// back to the system program, so it can't be revived within the same transaction
+pub fn close_account(acc: &AccountInfo, dest: &AccountInfo) -> ProgramResult {
     **dest.lamports.borrow_mut() = dest
         .lamports()
         .checked_add(acc.lamports())
         .ok_or(ProgramError::ArithmeticOverflow)?;
     **acc.lamports.borrow_mut() = 0;
 
-    acc.data.borrow_mut().fill(0);
+    acc.realloc(0, false)?;
+    acc.assign(&system_program::ID);
 
     Ok(())
 }
 
+pub fn unpack_option<T>(flag: u8, value: T) -> Result<Option<T>, ProgramError> {
+    match flag {
+        0 => Ok(None),
+        1 => Ok(Some(value)),
+        _ => {
+            msg!("[Error] Invalid option flag: {}", flag);
+            Err(ProgramError::InvalidInstructionData)
+        }
+    }
+}
+
+pub fn pack_option<const N: usize>(value: Option<[u8; N]>, flag: &mut [u8; 1], dst: &mut [u8; N]) {
+    match value {
+        Some(value) => {
+            flag[0] = 1;
+            *dst = value;
+        }
+        None => {
+            flag[0] = 0;
+            dst.fill(0);
+        }
+    }
+}
+
 pub fn parse_string(bytes: &[u8]) -> Result<String, ProgramError> {
     String::from_utf8(Vec::from(bytes)).map_err(|_| GaiminError::InvalidString.into())
 }
@@ -2,37 +2,2161 @@
 
 mod utils;
 
-use gaimin_staking::instruction::{ConfigArgs, GaiminInstruction};
-use solana_program::{instruction::{Instruction, AccountMeta}, system_program};
-use solana_program_test::tokio;
-use solana_sdk::{transaction::Transaction, signer::Signer};
+use gaimin_staking::{
+    error::GaiminError,
+    instruction::{
+        ClaimArgs, ConfigArgs, ConfigChangeArgs, CreateClaimArgs, GaiminInstruction,
+        SetEpochScheduleArgs, SetMultisigArgs, SetPauseArgs, SetRoleArgs, StakeArgs,
+    },
+    processor::{
+        campaign_seed, CLAIM_PDA_SEED, CONFIG_CHANGE_PDA_SEED, DEFAULT_CAMPAIGN_ID,
+        DELEGATE_PDA_SEED, EPOCH_SCHEDULE_PDA_SEED, MPL_TOKEN_METADATA_PROGRAM_ID,
+        MULTISIG_PDA_SEED, STAKE_LOCK_PDA_SEED,
+    },
+    state::{
+        BoostSchedule, BoostTier, ClaimRecord, Config, Epoch, EpochSchedule, NftRecord,
+        RewardCurve, RewardTier, Role, StakeLock, Versioned, BNB_CHAIN_WALLET_ADDRESS_LENGTH,
+        PAUSE_CLAIM, PAUSE_CREATE_CLAIM, REWARD_INDEX_SCALE,
+    },
+};
+use mpl_token_metadata::{
+    accounts::TokenRecord,
+    types::{TokenDelegateRole, TokenState},
+};
+use solana_program::{
+    clock::Clock,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction, system_program, sysvar,
+};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::{
+    account::AccountSharedData, signature::Keypair, signer::Signer, transaction::TransactionError,
+};
 use utils::*;
 
-#[tokio::test]
-async fn config() {
-    let mut context = program_test().start_with_context().await;
+const TIMELOCK_DURATION: i64 = 3600;
 
+fn config_instruction(
+    authority: Pubkey,
+    creator: Pubkey,
+    campaign_id: u64,
+    emission_rate: u64,
+) -> Instruction {
     let instruction = GaiminInstruction::Config(ConfigArgs {
+        campaign_id,
         claimable_from: 0,
-        total_reward: 40000.0,
-        initial_reward_frac: 0.2,
-        reward_period_sec: 9000,
+        claimable_until: i64::MAX,
+        accrual_ends_at: 0,
+        accumulated_reward: 32000,
+        initial_reward: 8000,
+        total_accumulation_period: 90000,
+        generation_duration: 300,
+        timelock_duration: TIMELOCK_DURATION,
+        reward_decimals: 2,
+        reward_curve: RewardCurve::Linear,
+        emission_budget: 0,
+        boost_schedule: BoostSchedule::default(),
+        emission_rate,
     });
 
-    let transaction = Transaction::new_signed_with_payer(
-        &[Instruction {
-            program_id: gaimin_staking::ID,
-            accounts: vec![
-                AccountMeta::new_readonly(context.payer.pubkey(), true),
-                AccountMeta::new(config_pda().0, false),
-                AccountMeta::new_readonly(system_program::ID, false),
+    Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new_readonly(creator, false),
+            AccountMeta::new(config_pda(campaign_id).0, false),
+            AccountMeta::new_readonly(program_data_address(), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: instruction.pack(),
+    }
+}
+
+async fn create_config(context: &mut ProgramTestContext, creator: Pubkey, campaign_id: u64) {
+    let authority = context.payer.pubkey();
+    set_upgrade_authority(context, &authority);
+
+    process(
+        context,
+        config_instruction(authority, creator, campaign_id, 0),
+        &[],
+    )
+    .await
+    .unwrap();
+}
+
+fn create_claim_instruction(wallet: Pubkey, seed: [u8; 32], campaign_id: u64) -> Instruction {
+    let (claim, bump) = claim_pda(wallet, seed, campaign_id);
+
+    Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new(wallet, true),
+            AccountMeta::new(claim, false),
+            AccountMeta::new_readonly(config_pda(campaign_id).0, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: GaiminInstruction::CreateClaim(CreateClaimArgs {
+            bump,
+            seed,
+            bnb_chain_wallet_address: "C3Fc58A10056fDF37b0AAAE295Ef3C42609988B8".to_string(),
+        })
+        .pack(),
+    }
+}
+
+fn claim_pda(wallet: Pubkey, seed: [u8; 32], campaign_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CLAIM_PDA_SEED,
+            campaign_seed(&campaign_id.to_le_bytes()),
+            &wallet.to_bytes(),
+            &seed,
+        ],
+        &gaimin_staking::ID,
+    )
+}
+
+fn epoch_schedule_pda(campaign_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            EPOCH_SCHEDULE_PDA_SEED,
+            campaign_seed(&campaign_id.to_le_bytes()),
+        ],
+        &gaimin_staking::ID,
+    )
+    .0
+}
+
+fn config_change_pda(campaign_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            CONFIG_CHANGE_PDA_SEED,
+            campaign_seed(&campaign_id.to_le_bytes()),
+        ],
+        &gaimin_staking::ID,
+    )
+    .0
+}
+
+fn schedule_config_change_instruction(
+    authority: Pubkey,
+    campaign_id: u64,
+    change: ConfigChangeArgs,
+) -> Instruction {
+    Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(authority, true),
+            AccountMeta::new_readonly(config_pda(campaign_id).0, false),
+            AccountMeta::new(config_change_pda(campaign_id), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: GaiminInstruction::ScheduleConfigChange(change).pack(),
+    }
+}
+
+fn apply_config_change_instruction(receiver: Pubkey, campaign_id: u64) -> Instruction {
+    Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new(config_pda(campaign_id).0, false),
+            AccountMeta::new(config_change_pda(campaign_id), false),
+            AccountMeta::new(receiver, false),
+        ],
+        data: GaiminInstruction::ApplyConfigChange.pack(),
+    }
+}
+
+/// Creates a wallet with enough lamports to pay for the rent of the accounts it creates
+fn new_wallet(context: &mut ProgramTestContext) -> Keypair {
+    let wallet = Keypair::new();
+    context.set_account(
+        &wallet.pubkey(),
+        &AccountSharedData::new(1_000_000_000, 0, &system_program::ID),
+    );
+    wallet
+}
+
+fn delegate_pda() -> Pubkey {
+    Pubkey::find_program_address(&[DELEGATE_PDA_SEED], &gaimin_staking::ID).0
+}
+
+fn stake_lock_pda(mint: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[STAKE_LOCK_PDA_SEED, mint.as_ref()], &gaimin_staking::ID).0
+}
+
+/// Accounts of the Token Metadata program passed to stake and unstake instructions
+fn token_metadata_accounts(mint: Pubkey) -> Vec<AccountMeta> {
+    let metadata_seeds = [
+        b"metadata".as_ref(),
+        MPL_TOKEN_METADATA_PROGRAM_ID.as_ref(),
+        mint.as_ref(),
+    ];
+    let metadata = Pubkey::find_program_address(&metadata_seeds, &MPL_TOKEN_METADATA_PROGRAM_ID).0;
+    let edition = Pubkey::find_program_address(
+        &[&metadata_seeds[..], &[b"edition"]].concat(),
+        &MPL_TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0;
+
+    vec![
+        AccountMeta::new(metadata, false),
+        AccountMeta::new_readonly(edition, false),
+        AccountMeta::new_readonly(delegate_pda(), false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(sysvar::instructions::ID, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new_readonly(MPL_TOKEN_METADATA_PROGRAM_ID, false),
+        // Authorization rules are omitted
+        AccountMeta::new_readonly(gaimin_staking::ID, false),
+        AccountMeta::new_readonly(gaimin_staking::ID, false),
+    ]
+}
+
+fn stake_instruction(wallet: Pubkey, mint: Pubkey, campaign_id: u64) -> Instruction {
+    let (token, token_acc_bump) = token_pda(&wallet, &mint);
+    let (token_record, token_record_bump) = token_record_pda(&mint, &token);
+    let (nft_record, nft_record_bump) = nft_pda(campaign_id, &mint);
+
+    Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: [
+            vec![
+                AccountMeta::new(wallet, true),
+                AccountMeta::new(token, false),
+                AccountMeta::new(token_record, false),
+                AccountMeta::new(nft_record, false),
+                AccountMeta::new(config_pda(campaign_id).0, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new(stake_lock_pda(mint), false),
             ],
-            data: instruction.pack(),
-        }],
-        Some(&context.payer.pubkey()),
-        &[&context.payer],
-        context.last_blockhash,
+            token_metadata_accounts(mint),
+        ]
+        .concat(),
+        data: GaiminInstruction::Stake(StakeArgs {
+            token_acc_bump,
+            token_record_bump,
+            nft_record_bump,
+        })
+        .pack(),
+    }
+}
+
+fn claim_instruction(wallet: Pubkey, mint: Pubkey, claim: Pubkey, campaign_id: u64) -> Instruction {
+    let (token, token_acc_bump) = token_pda(&wallet, &mint);
+    let (token_record, token_record_bump) = token_record_pda(&mint, &token);
+    let (nft_record, nft_record_bump) = nft_pda(campaign_id, &mint);
+
+    Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(wallet, true),
+            AccountMeta::new_readonly(token, false),
+            AccountMeta::new_readonly(token_record, false),
+            AccountMeta::new(nft_record, false),
+            AccountMeta::new(claim, false),
+            AccountMeta::new(config_pda(campaign_id).0, false),
+            AccountMeta::new_readonly(epoch_schedule_pda(campaign_id), false),
+        ],
+        data: GaiminInstruction::Claim(ClaimArgs {
+            token_acc_bump,
+            token_record_bump,
+            nft_record_bump,
+        })
+        .pack(),
+    }
+}
+
+fn unstake_instruction(wallet: Pubkey, mint: Pubkey, campaign_id: u64) -> Instruction {
+    let token = token_pda(&wallet, &mint).0;
+
+    Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: [
+            vec![
+                AccountMeta::new(wallet, true),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new(nft_pda(campaign_id, &mint).0, false),
+                AccountMeta::new(config_pda(campaign_id).0, false),
+                AccountMeta::new(token, false),
+                AccountMeta::new(token_record_pda(&mint, &token).0, false),
+                AccountMeta::new(stake_lock_pda(mint), false),
+                AccountMeta::new_readonly(epoch_schedule_pda(campaign_id), false),
+            ],
+            token_metadata_accounts(mint),
+        ]
+        .concat(),
+        data: GaiminInstruction::Unstake.pack(),
+    }
+}
+
+async fn get_nft_record(
+    context: &mut ProgramTestContext,
+    campaign_id: u64,
+    mint: Pubkey,
+) -> NftRecord {
+    let account = context
+        .banks_client
+        .get_account(nft_pda(campaign_id, &mint).0)
+        .await
+        .unwrap()
+        .unwrap();
+    NftRecord::unpack(&account.data).unwrap()
+}
+
+async fn get_token_record(
+    context: &mut ProgramTestContext,
+    owner: Pubkey,
+    mint: Pubkey,
+) -> TokenRecord {
+    let token_record = token_record_pda(&mint, &token_pda(&owner, &mint).0).0;
+    let account = context
+        .banks_client
+        .get_account(token_record)
+        .await
+        .unwrap()
+        .unwrap();
+    TokenRecord::safe_deserialize(&account.data).unwrap()
+}
+
+async fn get_config(context: &mut ProgramTestContext, campaign_id: u64) -> Config {
+    let account = context
+        .banks_client
+        .get_account(config_pda(campaign_id).0)
+        .await
+        .unwrap()
+        .unwrap();
+    Config::unpack_unchecked(&account.data).unwrap()
+}
+
+#[tokio::test]
+async fn config() {
+    let mut context = program_test().start_with_context().await;
+    let creator = Pubkey::new_unique();
+
+    create_config(&mut context, creator, DEFAULT_CAMPAIGN_ID).await;
+
+    let config = get_config(&mut context, DEFAULT_CAMPAIGN_ID).await;
+    assert_eq!(config.authority, context.payer.pubkey());
+    assert_eq!(config.creator, creator);
+    assert_eq!(config.accumulation_duration, 2);
+    assert_eq!(config.reward_decimals, 2);
+}
+
+#[tokio::test]
+async fn multiple_campaigns() {
+    let mut context = program_test().start_with_context().await;
+    let creators = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+    create_config(&mut context, creators[0], DEFAULT_CAMPAIGN_ID).await;
+    create_config(&mut context, creators[1], 1).await;
+
+    let config = get_config(&mut context, DEFAULT_CAMPAIGN_ID).await;
+    assert_eq!(config.campaign_id, DEFAULT_CAMPAIGN_ID);
+    assert_eq!(config.creator, creators[0]);
+
+    let config = get_config(&mut context, 1).await;
+    assert_eq!(config.campaign_id, 1);
+    assert_eq!(config.creator, creators[1]);
+}
+
+#[tokio::test]
+async fn config_requires_upgrade_authority() {
+    let mut context = program_test().start_with_context().await;
+    set_upgrade_authority(&mut context, &Pubkey::new_unique());
+
+    let instruction = config_instruction(
+        context.payer.pubkey(),
+        Pubkey::new_unique(),
+        DEFAULT_CAMPAIGN_ID,
+        0,
+    );
+    process(&mut context, instruction, &[]).await.unwrap_err();
+}
+
+#[tokio::test]
+async fn config_change() {
+    let mut context = program_test().start_with_context().await;
+    create_config(&mut context, Pubkey::new_unique(), DEFAULT_CAMPAIGN_ID).await;
+
+    let authority = context.payer.pubkey();
+    let config_change = config_change_pda(DEFAULT_CAMPAIGN_ID);
+
+    let creator = Pubkey::new_unique();
+    let schedule = schedule_config_change_instruction(
+        authority,
+        DEFAULT_CAMPAIGN_ID,
+        ConfigChangeArgs {
+            creator: Some(creator),
+            initial_reward: Some(1000),
+            emission_budget: Some(1_000_000),
+            ..Default::default()
+        },
+    );
+
+    process(&mut context, schedule, &[]).await.unwrap();
+
+    let apply = apply_config_change_instruction(authority, DEFAULT_CAMPAIGN_ID);
+    let result = process(&mut context, apply.clone(), &[]).await;
+    assert_error(result, GaiminError::TimelockNotExpired);
+
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp += TIMELOCK_DURATION;
+    context.set_sysvar(&clock);
+
+    process(&mut context, apply, &[]).await.unwrap();
+
+    let config = get_config(&mut context, DEFAULT_CAMPAIGN_ID).await;
+    assert_eq!(config.creator, creator);
+    assert_eq!(config.initial_reward, 1000);
+    assert_eq!(config.accumulated_reward, 32000);
+    assert_eq!(config.accumulation_duration, 2);
+    assert_eq!(config.emission_budget, 1_000_000);
+
+    // The rate didn't change, so the reward index wasn't checkpointed
+    assert_eq!(config.index_updated_at, 0);
+
+    let account = context
+        .banks_client
+        .get_account(config_change)
+        .await
+        .unwrap();
+    assert!(account.is_none());
+}
+
+#[tokio::test]
+async fn claim_window() {
+    let mut context = program_test().start_with_context().await;
+    create_config(&mut context, Pubkey::new_unique(), DEFAULT_CAMPAIGN_ID).await;
+
+    let authority = context.payer.pubkey();
+    let config = get_config(&mut context, DEFAULT_CAMPAIGN_ID).await;
+    assert_eq!(config.claimable_until, i64::MAX);
+    assert_eq!(config.accrual_ends_at, 0);
+
+    // Accrual can't end after claiming closes
+    let schedule = schedule_config_change_instruction(
+        authority,
+        DEFAULT_CAMPAIGN_ID,
+        ConfigChangeArgs {
+            claimable_until: Some(2000),
+            accrual_ends_at: Some(3000),
+            ..Default::default()
+        },
+    );
+    let result = process(&mut context, schedule, &[]).await;
+    assert_error(result, GaiminError::InvalidConfig);
+
+    // Claiming can't close before it opens
+    let schedule = schedule_config_change_instruction(
+        authority,
+        DEFAULT_CAMPAIGN_ID,
+        ConfigChangeArgs {
+            claimable_from: Some(2000),
+            claimable_until: Some(1000),
+            ..Default::default()
+        },
+    );
+    let result = process(&mut context, schedule, &[]).await;
+    assert_error(result, GaiminError::InvalidConfig);
+
+    let schedule = schedule_config_change_instruction(
+        authority,
+        DEFAULT_CAMPAIGN_ID,
+        ConfigChangeArgs {
+            claimable_until: Some(3000),
+            accrual_ends_at: Some(2000),
+            ..Default::default()
+        },
+    );
+    process(&mut context, schedule, &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn generation_duration() {
+    let mut context = program_test().start_with_context().await;
+    let authority = context.payer.pubkey();
+    set_upgrade_authority(&mut context, &authority);
+
+    // Claim record generations are derived by dividing by the duration
+    let mut config = config_instruction(authority, Pubkey::new_unique(), DEFAULT_CAMPAIGN_ID, 0);
+    let GaiminInstruction::Config(mut args) = GaiminInstruction::unpack(&config.data).unwrap()
+    else {
+        unreachable!()
+    };
+    args.generation_duration = 0;
+    config.data = GaiminInstruction::Config(args).pack();
+    let result = process(&mut context, config, &[]).await;
+    assert_error(result, GaiminError::InvalidConfig);
+
+    create_config(&mut context, Pubkey::new_unique(), DEFAULT_CAMPAIGN_ID).await;
+    let schedule = schedule_config_change_instruction(
+        authority,
+        DEFAULT_CAMPAIGN_ID,
+        ConfigChangeArgs {
+            generation_duration: Some(0),
+            ..Default::default()
+        },
+    );
+    let result = process(&mut context, schedule, &[]).await;
+    assert_error(result, GaiminError::InvalidConfig);
+}
+
+#[tokio::test]
+async fn reward_curve() {
+    let mut context = program_test().start_with_context().await;
+    create_config(&mut context, Pubkey::new_unique(), DEFAULT_CAMPAIGN_ID).await;
+
+    let authority = context.payer.pubkey();
+    let config = get_config(&mut context, DEFAULT_CAMPAIGN_ID).await;
+    assert_eq!(config.reward_curve, RewardCurve::Linear);
+
+    // Tiers must be ordered by their start
+    let tier = |starts_after, rate| RewardTier { starts_after, rate };
+    let schedule = schedule_config_change_instruction(
+        authority,
+        DEFAULT_CAMPAIGN_ID,
+        ConfigChangeArgs {
+            reward_curve: Some(RewardCurve::Tiered(vec![tier(0, 2), tier(0, 1)])),
+            ..Default::default()
+        },
+    );
+    let result = process(&mut context, schedule, &[]).await;
+    assert_error(result, GaiminError::InvalidConfig);
+
+    let schedule = schedule_config_change_instruction(
+        authority,
+        DEFAULT_CAMPAIGN_ID,
+        ConfigChangeArgs {
+            reward_curve: Some(RewardCurve::ExponentialDecay { half_life: 0 }),
+            ..Default::default()
+        },
+    );
+    let result = process(&mut context, schedule, &[]).await;
+    assert_error(result, GaiminError::InvalidConfig);
+
+    // Boost tiers must be ordered by the staking duration
+    let boost = |staked_for, multiplier_bps| BoostTier {
+        staked_for,
+        multiplier_bps,
+    };
+    let schedule = schedule_config_change_instruction(
+        authority,
+        DEFAULT_CAMPAIGN_ID,
+        ConfigChangeArgs {
+            boost_schedule: Some(BoostSchedule {
+                tiers: vec![boost(7200, 15_000), boost(3600, 12_500)],
+            }),
+            ..Default::default()
+        },
+    );
+    let result = process(&mut context, schedule, &[]).await;
+    assert_error(result, GaiminError::InvalidConfig);
+
+    let curve = RewardCurve::Tiered(vec![tier(0, 2), tier(3600, 1)]);
+    let boost_schedule = BoostSchedule {
+        tiers: vec![boost(3600, 12_500), boost(7200, 15_000)],
+    };
+    let schedule = schedule_config_change_instruction(
+        authority,
+        DEFAULT_CAMPAIGN_ID,
+        ConfigChangeArgs {
+            reward_curve: Some(curve.clone()),
+            boost_schedule: Some(boost_schedule.clone()),
+            ..Default::default()
+        },
+    );
+    process(&mut context, schedule, &[]).await.unwrap();
+
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp += TIMELOCK_DURATION;
+    context.set_sysvar(&clock);
+
+    let apply = apply_config_change_instruction(authority, DEFAULT_CAMPAIGN_ID);
+    process(&mut context, apply, &[]).await.unwrap();
+
+    let config = get_config(&mut context, DEFAULT_CAMPAIGN_ID).await;
+    assert_eq!(config.reward_curve, curve);
+    assert_eq!(config.boost_schedule, boost_schedule);
+
+    // Rewards accrued linearly until the curve changed
+    assert_eq!(config.index_updated_at, clock.unix_timestamp);
+    assert_eq!(
+        config.reward_index,
+        clock.unix_timestamp as u128 * REWARD_INDEX_SCALE / 2
+    );
+}
+
+#[tokio::test]
+async fn pool_mode() {
+    let mut context = program_test().start_with_context().await;
+    create_config(&mut context, Pubkey::new_unique(), DEFAULT_CAMPAIGN_ID).await;
+
+    let authority = context.payer.pubkey();
+    process(
+        &mut context,
+        config_instruction(authority, Pubkey::new_unique(), 1, 100),
+        &[],
+    )
+    .await
+    .unwrap();
+    let config = get_config(&mut context, 1).await;
+    assert_eq!(config.emission_rate, 100);
+    assert_eq!(config.staked_count, 0);
+
+    // A campaign can't switch to pool mode
+    let schedule = schedule_config_change_instruction(
+        authority,
+        DEFAULT_CAMPAIGN_ID,
+        ConfigChangeArgs {
+            emission_rate: Some(100),
+            ..Default::default()
+        },
+    );
+    let result = process(&mut context, schedule, &[]).await;
+    assert_error(result, GaiminError::InvalidConfig);
+
+    // Nor can a pool switch to fixed allowances
+    let mut schedule = schedule_config_change_instruction(
+        authority,
+        1,
+        ConfigChangeArgs {
+            emission_rate: Some(0),
+            ..Default::default()
+        },
+    );
+    let result = process(&mut context, schedule.clone(), &[]).await;
+    assert_error(result, GaiminError::InvalidConfig);
+
+    // Boosts and epochs would exceed the emission of a pool
+    schedule.data = GaiminInstruction::ScheduleConfigChange(ConfigChangeArgs {
+        boost_schedule: Some(BoostSchedule {
+            tiers: vec![BoostTier {
+                staked_for: 3600,
+                multiplier_bps: 15_000,
+            }],
+        }),
+        ..Default::default()
+    })
+    .pack();
+    let result = process(&mut context, schedule.clone(), &[]).await;
+    assert_error(result, GaiminError::InvalidConfig);
+
+    let set_epoch_schedule = Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(authority, true),
+            AccountMeta::new(
+                Pubkey::find_program_address(
+                    &[EPOCH_SCHEDULE_PDA_SEED, campaign_seed(&1u64.to_le_bytes())],
+                    &gaimin_staking::ID,
+                )
+                .0,
+                false,
+            ),
+            AccountMeta::new_readonly(config_pda(1).0, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: GaiminInstruction::SetEpochSchedule(SetEpochScheduleArgs {
+            epochs: vec![Epoch {
+                starts_at: i64::MAX,
+                rate_bps: 20_000,
+                initial_bonus: 0,
+            }],
+        })
+        .pack(),
+    };
+    let result = process(&mut context, set_epoch_schedule, &[]).await;
+    assert_error(result, GaiminError::InvalidConfig);
+
+    // Fixed allowances accrue over the accumulation duration
+    let schedule_default = schedule_config_change_instruction(
+        authority,
+        DEFAULT_CAMPAIGN_ID,
+        ConfigChangeArgs {
+            accumulation_duration: Some(0),
+            ..Default::default()
+        },
+    );
+    let result = process(&mut context, schedule_default, &[]).await;
+    assert_error(result, GaiminError::InvalidConfig);
+
+    // The emission rate of a pool can change, and it doesn't use the accumulation duration
+    schedule.data = GaiminInstruction::ScheduleConfigChange(ConfigChangeArgs {
+        emission_rate: Some(200),
+        accumulation_duration: Some(0),
+        ..Default::default()
+    })
+    .pack();
+    process(&mut context, schedule, &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn stake() {
+    let mut context = program_test().start_with_context().await;
+    let authority = context.payer.pubkey();
+    set_upgrade_authority(&mut context, &authority);
+    process(
+        &mut context,
+        config_instruction(authority, Pubkey::new_unique(), 1, 100),
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let wallet = new_wallet(&mut context);
+    let mint = Pubkey::new_unique();
+    set_nft_record(
+        &mut context,
+        1,
+        &mint,
+        NftRecord {
+            claimed_amount: 0,
+            total_amount: u64::MAX,
+            last_claim_at: 0,
+            payer: authority,
+            staked_since: 0,
+            staker: Pubkey::default(),
+            reward_index: Some(0),
+            reward_remainder: 0,
+            pending_reward: 0,
+        },
+    );
+
+    // Staking delegates the token to the program and locks it
+    set_token(
+        &mut context,
+        &wallet.pubkey(),
+        &mint,
+        TokenState::Unlocked,
+        None,
+    );
+    process(
+        &mut context,
+        stake_instruction(wallet.pubkey(), mint, 1),
+        &[&wallet],
+    )
+    .await
+    .unwrap();
+
+    // Rewards accrue from the time of staking
+    let now = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp;
+    let nft_record = get_nft_record(&mut context, 1, mint).await;
+    assert_eq!(nft_record.staker, wallet.pubkey());
+    assert_eq!(nft_record.staked_since, now);
+    assert_eq!(nft_record.last_claim_at, now);
+    assert_eq!(get_config(&mut context, 1).await.staked_count, 1);
+
+    let token_record = get_token_record(&mut context, wallet.pubkey(), mint).await;
+    assert_eq!(token_record.state, TokenState::Locked);
+    assert_eq!(token_record.delegate, Some(delegate_pda()));
+    assert_eq!(token_record.delegate_role, Some(TokenDelegateRole::Staking));
+
+    let result = process(
+        &mut context,
+        stake_instruction(wallet.pubkey(), mint, 1),
+        &[&wallet],
+    )
+    .await;
+    assert_error(result, GaiminError::AlreadyStaked);
+
+    // Another holder can't take the NFT over from the staker
+    let holder = Keypair::new();
+    set_token(
+        &mut context,
+        &holder.pubkey(),
+        &mint,
+        TokenState::Unlocked,
+        None,
+    );
+    let result = process(
+        &mut context,
+        stake_instruction(holder.pubkey(), mint, 1),
+        &[&holder],
+    )
+    .await;
+    assert_error(result, GaiminError::AlreadyStaked);
+    assert_eq!(
+        get_nft_record(&mut context, 1, mint).await.staker,
+        wallet.pubkey()
+    );
+
+    // Only the owner can unstake
+    let result = process(&mut context, unstake_instruction(authority, mint, 1), &[]).await;
+    assert_error(result, GaiminError::InvalidTokenAccount);
+
+    process(
+        &mut context,
+        unstake_instruction(wallet.pubkey(), mint, 1),
+        &[&wallet],
+    )
+    .await
+    .unwrap();
+    let nft_record = get_nft_record(&mut context, 1, mint).await;
+    assert_eq!(nft_record.staker, wallet.pubkey());
+    assert_eq!(nft_record.staked_since, 0);
+    assert_eq!(get_config(&mut context, 1).await.staked_count, 0);
+
+    let token_record = get_token_record(&mut context, wallet.pubkey(), mint).await;
+    assert_eq!(token_record.state, TokenState::Unlocked);
+    assert_eq!(token_record.delegate, None);
+
+    let result = process(
+        &mut context,
+        unstake_instruction(wallet.pubkey(), mint, 1),
+        &[&wallet],
+    )
+    .await;
+    assert_error(result, GaiminError::NotStaked);
+}
+
+/// Stakes the NFT of `wallet` in campaign 1 and unstakes it `duration` seconds later
+async fn stake_for(
+    context: &mut ProgramTestContext,
+    wallet: &Keypair,
+    mint: Pubkey,
+    duration: i64,
+) {
+    process(
+        context,
+        stake_instruction(wallet.pubkey(), mint, 1),
+        &[wallet],
+    )
+    .await
+    .unwrap();
+
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp += duration;
+    context.set_sysvar(&clock);
+
+    process(
+        context,
+        unstake_instruction(wallet.pubkey(), mint, 1),
+        &[wallet],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn unstake_settles_rewards() {
+    let mut context = program_test().start_with_context().await;
+    let authority = context.payer.pubkey();
+    set_upgrade_authority(&mut context, &authority);
+    process(
+        &mut context,
+        config_instruction(authority, Pubkey::new_unique(), 1, 100),
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let wallet = new_wallet(&mut context);
+    let mint = Pubkey::new_unique();
+    set_nft_record(
+        &mut context,
+        1,
+        &mint,
+        NftRecord {
+            claimed_amount: 0,
+            total_amount: u64::MAX,
+            last_claim_at: 0,
+            payer: authority,
+            staked_since: 0,
+            staker: Pubkey::default(),
+            reward_index: Some(0),
+            reward_remainder: 0,
+            pending_reward: 0,
+        },
+    );
+    set_token(
+        &mut context,
+        &wallet.pubkey(),
+        &mint,
+        TokenState::Unlocked,
+        None,
+    );
+    process(
+        &mut context,
+        create_claim_instruction(wallet.pubkey(), [0; 32], 1),
+        &[&wallet],
+    )
+    .await
+    .unwrap();
+    let claim = claim_pda(wallet.pubkey(), [0; 32], 1).0;
+
+    // The initial reward and the pool share of the only staked NFT are settled on unstaking
+    stake_for(&mut context, &wallet, mint, 1000).await;
+    let nft_record = get_nft_record(&mut context, 1, mint).await;
+    assert_eq!(nft_record.staker, wallet.pubkey());
+    assert_eq!(nft_record.pending_reward, 8000 + 100 * 1000);
+
+    // The staker claims them without staking again
+    process(
+        &mut context,
+        claim_instruction(wallet.pubkey(), mint, claim, 1),
+        &[&wallet],
+    )
+    .await
+    .unwrap();
+    let account = context
+        .banks_client
+        .get_account(claim)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(ClaimRecord::unpack(&account.data).unwrap().amount, 108000);
+    let nft_record = get_nft_record(&mut context, 1, mint).await;
+    assert_eq!(nft_record.pending_reward, 0);
+    assert_eq!(nft_record.claimed_amount, 108000);
+    assert_eq!(get_config(&mut context, 1).await.claimed_reward, 108000);
+
+    // Nothing accrues once the settled reward is claimed
+    let result = process(
+        &mut context,
+        claim_instruction(wallet.pubkey(), mint, claim, 1),
+        &[&wallet],
+    )
+    .await;
+    assert_error(result, GaiminError::NotStaked);
+
+    // The settled reward is forfeited once another wallet stakes the NFT
+    stake_for(&mut context, &wallet, mint, 500).await;
+    assert_eq!(
+        get_nft_record(&mut context, 1, mint).await.pending_reward,
+        100 * 500
+    );
+    let holder = new_wallet(&mut context);
+    set_token(
+        &mut context,
+        &holder.pubkey(),
+        &mint,
+        TokenState::Unlocked,
+        None,
+    );
+    process(
+        &mut context,
+        stake_instruction(holder.pubkey(), mint, 1),
+        &[&holder],
+    )
+    .await
+    .unwrap();
+    let nft_record = get_nft_record(&mut context, 1, mint).await;
+    assert_eq!(nft_record.staker, holder.pubkey());
+    assert_eq!(nft_record.pending_reward, 0);
+}
+
+#[tokio::test]
+async fn stake_in_multiple_campaigns() {
+    let mut context = program_test().start_with_context().await;
+    let authority = context.payer.pubkey();
+    set_upgrade_authority(&mut context, &authority);
+
+    let wallet = new_wallet(&mut context);
+    let mint = Pubkey::new_unique();
+    for campaign_id in [1, 2] {
+        process(
+            &mut context,
+            config_instruction(authority, Pubkey::new_unique(), campaign_id, 100),
+            &[],
+        )
+        .await
+        .unwrap();
+        set_nft_record(
+            &mut context,
+            campaign_id,
+            &mint,
+            NftRecord {
+                claimed_amount: 0,
+                total_amount: u64::MAX,
+                last_claim_at: 0,
+                payer: authority,
+                staked_since: 0,
+                staker: Pubkey::default(),
+                reward_index: Some(0),
+                reward_remainder: 0,
+                pending_reward: 0,
+            },
+        );
+    }
+    set_token(
+        &mut context,
+        &wallet.pubkey(),
+        &mint,
+        TokenState::Unlocked,
+        None,
+    );
+
+    // The lock of the first campaign is shared by the second one
+    for campaign_id in [1, 2] {
+        process(
+            &mut context,
+            stake_instruction(wallet.pubkey(), mint, campaign_id),
+            &[&wallet],
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            get_nft_record(&mut context, campaign_id, mint).await.staker,
+            wallet.pubkey()
+        );
+    }
+    let account = context
+        .banks_client
+        .get_account(stake_lock_pda(mint))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        StakeLock::unpack(&account.data).unwrap().campaign_ids,
+        vec![1, 2]
+    );
+
+    // The token stays locked until the NFT is unstaked from every campaign
+    process(
+        &mut context,
+        unstake_instruction(wallet.pubkey(), mint, 1),
+        &[&wallet],
+    )
+    .await
+    .unwrap();
+    let token_record = get_token_record(&mut context, wallet.pubkey(), mint).await;
+    assert_eq!(token_record.state, TokenState::Locked);
+    assert_eq!(token_record.delegate, Some(delegate_pda()));
+    assert_eq!(get_nft_record(&mut context, 1, mint).await.staked_since, 0);
+
+    process(
+        &mut context,
+        unstake_instruction(wallet.pubkey(), mint, 2),
+        &[&wallet],
+    )
+    .await
+    .unwrap();
+    let token_record = get_token_record(&mut context, wallet.pubkey(), mint).await;
+    assert_eq!(token_record.state, TokenState::Unlocked);
+    assert_eq!(token_record.delegate, None);
+
+    let account = context
+        .banks_client
+        .get_account(stake_lock_pda(mint))
+        .await
+        .unwrap();
+    assert!(account.is_none());
+}
+
+/// Schedules `change` and applies it once the timelock has passed
+async fn change_config(
+    context: &mut ProgramTestContext,
+    campaign_id: u64,
+    change: ConfigChangeArgs,
+) {
+    let authority = context.payer.pubkey();
+    let schedule = schedule_config_change_instruction(authority, campaign_id, change);
+    process(context, schedule, &[]).await.unwrap();
+
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp += TIMELOCK_DURATION;
+    context.set_sysvar(&clock);
+
+    let apply = apply_config_change_instruction(authority, campaign_id);
+    process(context, apply, &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn claim() {
+    let mut context = program_test().start_with_context().await;
+    let authority = context.payer.pubkey();
+    set_upgrade_authority(&mut context, &authority);
+    process(
+        &mut context,
+        config_instruction(authority, Pubkey::new_unique(), 1, 0),
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let wallet = new_wallet(&mut context);
+    let mint = Pubkey::new_unique();
+    set_nft_record(
+        &mut context,
+        1,
+        &mint,
+        NftRecord {
+            claimed_amount: 0,
+            total_amount: 40000,
+            last_claim_at: 0,
+            payer: authority,
+            staked_since: 0,
+            staker: Pubkey::default(),
+            reward_index: Some(0),
+            reward_remainder: 0,
+            pending_reward: 0,
+        },
+    );
+    set_token(
+        &mut context,
+        &wallet.pubkey(),
+        &mint,
+        TokenState::Unlocked,
+        None,
+    );
+    process(
+        &mut context,
+        create_claim_instruction(wallet.pubkey(), [0; 32], 1),
+        &[&wallet],
+    )
+    .await
+    .unwrap();
+    let claim = claim_pda(wallet.pubkey(), [0; 32], 1).0;
+    let claim_reward = || claim_instruction(wallet.pubkey(), mint, claim, 1);
+
+    // Rewards only accrue while the NFT is staked
+    let result = process(&mut context, claim_reward(), &[&wallet]).await;
+    assert_error(result, GaiminError::NotStaked);
+
+    process(
+        &mut context,
+        stake_instruction(wallet.pubkey(), mint, 1),
+        &[&wallet],
+    )
+    .await
+    .unwrap();
+
+    let set_pause = |paused: u8| Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(config_pda(1).0, false),
+        ],
+        data: GaiminInstruction::SetPause(SetPauseArgs { paused }).pack(),
+    };
+    process(&mut context, set_pause(PAUSE_CLAIM), &[])
+        .await
+        .unwrap();
+    let result = process(&mut context, claim_reward(), &[&wallet]).await;
+    assert_error(result, GaiminError::Paused);
+    process(&mut context, set_pause(0), &[]).await.unwrap();
+
+    // The reward is added to the claim record and counted as claimed by the NFT and the campaign
+    process(&mut context, claim_reward(), &[&wallet])
+        .await
+        .unwrap();
+    let account = context
+        .banks_client
+        .get_account(claim)
+        .await
+        .unwrap()
+        .unwrap();
+    let amount = ClaimRecord::unpack(&account.data).unwrap().amount;
+    assert!(amount >= 8000);
+    assert_eq!(
+        get_nft_record(&mut context, 1, mint).await.claimed_amount,
+        amount
+    );
+    assert_eq!(get_config(&mut context, 1).await.claimed_reward, amount);
+
+    // Nothing can be claimed once the emission budget has been claimed
+    change_config(
+        &mut context,
+        1,
+        ConfigChangeArgs {
+            emission_budget: Some(amount),
+            ..Default::default()
+        },
+    )
+    .await;
+    let result = process(&mut context, claim_reward(), &[&wallet]).await;
+    assert_error(result, GaiminError::BudgetExhausted);
+
+    // Rewards accrued until the end of accrual are claimed once
+    let now = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp;
+    change_config(
+        &mut context,
+        1,
+        ConfigChangeArgs {
+            emission_budget: Some(0),
+            accrual_ends_at: Some(now),
+            ..Default::default()
+        },
+    )
+    .await;
+    process(&mut context, claim_reward(), &[&wallet])
+        .await
+        .unwrap();
+    let nft_record = get_nft_record(&mut context, 1, mint).await;
+    assert!(nft_record.claimed_amount > amount);
+    assert_eq!(nft_record.last_claim_at, now);
+
+    let result = process(&mut context, claim_reward(), &[&wallet]).await;
+    assert_error(result, GaiminError::AccrualFinished);
+
+    change_config(
+        &mut context,
+        1,
+        ConfigChangeArgs {
+            claimable_until: Some(now),
+            ..Default::default()
+        },
+    )
+    .await;
+    let result = process(&mut context, claim_reward(), &[&wallet]).await;
+    assert_error(result, GaiminError::ClaimingClosed);
+}
+
+#[tokio::test]
+async fn foreign_lock() {
+    let mut context = program_test().start_with_context().await;
+    let authority = context.payer.pubkey();
+    set_upgrade_authority(&mut context, &authority);
+    process(
+        &mut context,
+        config_instruction(authority, Pubkey::new_unique(), 1, 100),
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let wallet = Keypair::new();
+    let rented = Pubkey::new_unique();
+    let listed = Pubkey::new_unique();
+    let renter = Pubkey::new_unique();
+    for mint in [rented, listed] {
+        set_nft_record(
+            &mut context,
+            1,
+            &mint,
+            NftRecord {
+                claimed_amount: 0,
+                total_amount: u64::MAX,
+                last_claim_at: 0,
+                payer: authority,
+                staked_since: 0,
+                staker: Pubkey::default(),
+                reward_index: Some(0),
+                reward_remainder: 0,
+                pending_reward: 0,
+            },
+        );
+    }
+    set_token(
+        &mut context,
+        &wallet.pubkey(),
+        &rented,
+        TokenState::Locked,
+        Some((renter, TokenDelegateRole::Utility)),
+    );
+    set_token(
+        &mut context,
+        &wallet.pubkey(),
+        &listed,
+        TokenState::Locked,
+        Some((renter, TokenDelegateRole::Sale)),
+    );
+
+    // NFTs locked by other delegates can't be staked nor claimed for
+    for mint in [rented, listed] {
+        let result = process(
+            &mut context,
+            stake_instruction(wallet.pubkey(), mint, 1),
+            &[&wallet],
+        )
+        .await;
+        assert_error(result, GaiminError::ForeignLock);
+
+        // The lock may be replaced once the NFT is staked
+        set_nft_record(
+            &mut context,
+            1,
+            &mint,
+            NftRecord {
+                claimed_amount: 0,
+                total_amount: u64::MAX,
+                last_claim_at: 0,
+                payer: authority,
+                staked_since: 1,
+                staker: wallet.pubkey(),
+                reward_index: Some(0),
+                reward_remainder: 0,
+                pending_reward: 0,
+            },
+        );
+
+        let result = process(
+            &mut context,
+            claim_instruction(
+                wallet.pubkey(),
+                mint,
+                claim_pda(wallet.pubkey(), [0; 32], 1).0,
+                1,
+            ),
+            &[&wallet],
+        )
+        .await;
+        assert_error(result, GaiminError::ForeignLock);
+    }
+    assert_eq!(get_config(&mut context, 1).await.staked_count, 0);
+}
+
+#[tokio::test]
+async fn transfer_authority() {
+    let mut context = program_test().start_with_context().await;
+    create_config(&mut context, Pubkey::new_unique(), DEFAULT_CAMPAIGN_ID).await;
+
+    let new_authority = Keypair::new();
+    let propose = Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(context.payer.pubkey(), true),
+            AccountMeta::new_readonly(new_authority.pubkey(), false),
+            AccountMeta::new(config_pda(DEFAULT_CAMPAIGN_ID).0, false),
+        ],
+        data: GaiminInstruction::ProposeAuthority.pack(),
+    };
+
+    process(&mut context, propose, &[]).await.unwrap();
+
+    let config = get_config(&mut context, DEFAULT_CAMPAIGN_ID).await;
+    assert_eq!(config.authority, context.payer.pubkey());
+    assert_eq!(config.pending_authority, new_authority.pubkey());
+
+    let accept = |signer: Pubkey| Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(signer, true),
+            AccountMeta::new(config_pda(DEFAULT_CAMPAIGN_ID).0, false),
+        ],
+        data: GaiminInstruction::AcceptAuthority.pack(),
+    };
+
+    let impostor = Keypair::new();
+    process(&mut context, accept(impostor.pubkey()), &[&impostor])
+        .await
+        .unwrap_err();
+
+    process(
+        &mut context,
+        accept(new_authority.pubkey()),
+        &[&new_authority],
+    )
+    .await
+    .unwrap();
+
+    let config = get_config(&mut context, DEFAULT_CAMPAIGN_ID).await;
+    assert_eq!(config.authority, new_authority.pubkey());
+    assert_eq!(config.pending_authority, Pubkey::default());
+}
+
+#[tokio::test]
+async fn pause() {
+    let mut context = program_test().start_with_context().await;
+    create_config(&mut context, Pubkey::new_unique(), DEFAULT_CAMPAIGN_ID).await;
+
+    let set_pause = |authority: Pubkey, paused: u8| Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(config_pda(DEFAULT_CAMPAIGN_ID).0, false),
+        ],
+        data: GaiminInstruction::SetPause(SetPauseArgs { paused }).pack(),
+    };
+
+    let wallet = context.payer.pubkey();
+    let create_claim = || create_claim_instruction(wallet, [0; 32], DEFAULT_CAMPAIGN_ID);
+
+    process(&mut context, set_pause(wallet, PAUSE_CREATE_CLAIM), &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        get_config(&mut context, DEFAULT_CAMPAIGN_ID).await.paused,
+        PAUSE_CREATE_CLAIM
+    );
+
+    let result = process(&mut context, create_claim(), &[]).await;
+    assert_error(result, GaiminError::Paused);
+
+    process(&mut context, set_pause(wallet, 0), &[])
+        .await
+        .unwrap();
+    process(&mut context, create_claim(), &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn prefunded_accounts() {
+    let mut context = program_test().start_with_context().await;
+    let wallet = context.payer.pubkey();
+    let config = config_pda(DEFAULT_CAMPAIGN_ID).0;
+    let claim = claim_pda(wallet, [2; 32], DEFAULT_CAMPAIGN_ID).0;
+
+    // Lamports sent to a PDA ahead of time must not block its creation
+    for address in [config, claim] {
+        let transfer = system_instruction::transfer(&wallet, &address, 1_000_000);
+        process(&mut context, transfer, &[]).await.unwrap();
+    }
+
+    create_config(&mut context, Pubkey::new_unique(), DEFAULT_CAMPAIGN_ID).await;
+    process(
+        &mut context,
+        create_claim_instruction(wallet, [2; 32], DEFAULT_CAMPAIGN_ID),
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let rent = context.banks_client.get_rent().await.unwrap();
+    for (address, len) in [(config, Config::LEN), (claim, ClaimRecord::LEN)] {
+        let account = context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(account.owner, gaimin_staking::ID);
+        assert_eq!(account.data.len(), len);
+        assert_eq!(account.lamports, rent.minimum_balance(len));
+    }
+}
+
+#[tokio::test]
+async fn foreign_config() {
+    let mut context = program_test().start_with_context().await;
+    create_config(&mut context, Pubkey::new_unique(), DEFAULT_CAMPAIGN_ID).await;
+
+    // Same data and address, but owned by another program
+    let config = config_pda(DEFAULT_CAMPAIGN_ID).0;
+    let mut account = context
+        .banks_client
+        .get_account(config)
+        .await
+        .unwrap()
+        .unwrap();
+    account.owner = system_program::ID;
+    context.set_account(&config, &account.into());
+
+    let set_pause = Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(context.payer.pubkey(), true),
+            AccountMeta::new(config, false),
+        ],
+        data: GaiminInstruction::SetPause(SetPauseArgs {
+            paused: PAUSE_CLAIM,
+        })
+        .pack(),
+    };
+    let result = process(&mut context, set_pause, &[]).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::IncorrectProgramId),
+    );
+}
+
+#[tokio::test]
+async fn settler_role() {
+    let mut context = program_test().start_with_context().await;
+    create_config(&mut context, Pubkey::new_unique(), DEFAULT_CAMPAIGN_ID).await;
+
+    let settler = Keypair::new();
+    let set_role = Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(context.payer.pubkey(), true),
+            AccountMeta::new_readonly(settler.pubkey(), false),
+            AccountMeta::new(config_pda(DEFAULT_CAMPAIGN_ID).0, false),
+        ],
+        data: GaiminInstruction::SetRole(SetRoleArgs {
+            role: Role::Settler,
+        })
+        .pack(),
+    };
+
+    process(&mut context, set_role, &[]).await.unwrap();
+    assert_eq!(
+        get_config(&mut context, DEFAULT_CAMPAIGN_ID).await.settler,
+        settler.pubkey()
+    );
+
+    let wallet = context.payer.pubkey();
+    process(
+        &mut context,
+        create_claim_instruction(wallet, [1; 32], DEFAULT_CAMPAIGN_ID),
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let close_claim = |target: Pubkey| Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(settler.pubkey(), true),
+            AccountMeta::new(target, false),
+            AccountMeta::new(wallet, false),
+            AccountMeta::new_readonly(config_pda(DEFAULT_CAMPAIGN_ID).0, false),
+        ],
+        data: GaiminInstruction::CloseClaimRecord.pack(),
+    };
+
+    // Other accounts can't be passed as a claim record
+    let result = process(
+        &mut context,
+        close_claim(config_pda(DEFAULT_CAMPAIGN_ID).0),
+        &[&settler],
+    )
+    .await;
+    assert_error(result, GaiminError::InvalidAccountType);
+
+    let result = process(&mut context, close_claim(wallet), &[&settler]).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::IncorrectProgramId),
+    );
+
+    // Claim records are kept until their generation ends
+    let claim = claim_pda(wallet, [1; 32], DEFAULT_CAMPAIGN_ID).0;
+    let result = process(&mut context, close_claim(claim), &[&settler]).await;
+    assert_error(result, GaiminError::AccountInUse);
+
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp += 300;
+    context.set_sysvar(&clock);
+
+    // Only settled claim records can be closed
+    let result = process(&mut context, close_claim(claim), &[&settler]).await;
+    assert_error(result, GaiminError::AccountInUse);
+
+    let settle_claim = Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(settler.pubkey(), true),
+            AccountMeta::new(claim, false),
+            AccountMeta::new_readonly(config_pda(DEFAULT_CAMPAIGN_ID).0, false),
+        ],
+        data: GaiminInstruction::SettleClaimRecord.pack(),
+    };
+    process(&mut context, settle_claim.clone(), &[&settler])
+        .await
+        .unwrap();
+    let account = context
+        .banks_client
+        .get_account(claim)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(ClaimRecord::unpack(&account.data).unwrap().settled);
+
+    let result = process(&mut context, settle_claim, &[&settler]).await;
+    assert_error(result, GaiminError::ClaimSettled);
+
+    process(&mut context, close_claim(claim), &[&settler])
+        .await
+        .unwrap();
+    let account = context.banks_client.get_account(claim).await.unwrap();
+    assert!(account.is_none());
+}
+
+#[tokio::test]
+async fn close_config() {
+    let mut context = program_test().start_with_context().await;
+    create_config(&mut context, Pubkey::new_unique(), DEFAULT_CAMPAIGN_ID).await;
+
+    let authority = context.payer.pubkey();
+    let config = config_pda(DEFAULT_CAMPAIGN_ID).0;
+    let epoch_schedule =
+        Pubkey::find_program_address(&[EPOCH_SCHEDULE_PDA_SEED], &gaimin_staking::ID).0;
+    let multisig = Pubkey::find_program_address(&[MULTISIG_PDA_SEED], &gaimin_staking::ID).0;
+    let close_config = |receiver: Pubkey| Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(config, false),
+            AccountMeta::new(receiver, false),
+            AccountMeta::new_readonly(config_change_pda(DEFAULT_CAMPAIGN_ID), false),
+            AccountMeta::new_readonly(epoch_schedule, false),
+            AccountMeta::new(multisig, false),
+        ],
+        data: GaiminInstruction::CloseConfig.pack(),
+    };
+
+    // The instruction code of `Delete` of version 0 is no longer accepted
+    let mut delete = close_config(authority);
+    delete.data = vec![1];
+    let result = process(&mut context, delete, &[]).await;
+    assert_error(result, GaiminError::InvalidInstruction);
+
+    // Rent is only returned to the payer
+    let result = process(&mut context, close_config(Pubkey::new_unique()), &[]).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+
+    // The config is kept while the campaign has NFT records
+    let mut account = context
+        .banks_client
+        .get_account(config)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut state = Config::unpack(&account.data).unwrap();
+    state.nft_count = 1;
+    Config::pack(state, &mut account.data).unwrap();
+    context.set_account(&config, &account.clone().into());
+
+    let result = process(&mut context, close_config(authority), &[]).await;
+    assert_error(result, GaiminError::AccountInUse);
+
+    let mut state = Config::unpack(&account.data).unwrap();
+    state.nft_count = 0;
+    Config::pack(state, &mut account.data).unwrap();
+    context.set_account(&config, &account.into());
+
+    // A pending config change must be cancelled first
+    let change = schedule_config_change_instruction(
+        authority,
+        DEFAULT_CAMPAIGN_ID,
+        ConfigChangeArgs {
+            initial_reward: Some(1000),
+            ..Default::default()
+        },
+    );
+    process(&mut context, change, &[]).await.unwrap();
+
+    let result = process(&mut context, close_config(authority), &[]).await;
+    assert_error(result, GaiminError::AccountInUse);
+
+    let cancel = Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new_readonly(config, false),
+            AccountMeta::new(config_change_pda(DEFAULT_CAMPAIGN_ID), false),
+            AccountMeta::new(authority, false),
+        ],
+        data: GaiminInstruction::CancelConfigChange.pack(),
+    };
+    process(&mut context, cancel, &[]).await.unwrap();
+
+    // An epoch schedule must be closed first
+    let now = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp;
+    let set_epoch_schedule = Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(authority, true),
+            AccountMeta::new(epoch_schedule, false),
+            AccountMeta::new_readonly(config, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: GaiminInstruction::SetEpochSchedule(SetEpochScheduleArgs {
+            epochs: vec![Epoch {
+                starts_at: now + TIMELOCK_DURATION + 100,
+                rate_bps: 20_000,
+                initial_bonus: 0,
+            }],
+        })
+        .pack(),
+    };
+    process(&mut context, set_epoch_schedule, &[])
+        .await
+        .unwrap();
+
+    let result = process(&mut context, close_config(authority), &[]).await;
+    assert_error(result, GaiminError::AccountInUse);
+
+    let close_epoch_schedule = Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(epoch_schedule, false),
+            AccountMeta::new(authority, false),
+            AccountMeta::new_readonly(config, false),
+        ],
+        data: GaiminInstruction::CloseEpochSchedule.pack(),
+    };
+    process(&mut context, close_epoch_schedule, &[])
+        .await
+        .unwrap();
+
+    // The multisig is closed along with the config
+    let set_multisig = Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(authority, true),
+            AccountMeta::new(multisig, false),
+            AccountMeta::new_readonly(config, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: GaiminInstruction::SetMultisig(SetMultisigArgs {
+            threshold: 1,
+            signers: vec![authority],
+        })
+        .pack(),
+    };
+    process(&mut context, set_multisig, &[]).await.unwrap();
+
+    process(&mut context, close_config(authority), &[])
+        .await
+        .unwrap();
+    for address in [config, multisig] {
+        let account = context.banks_client.get_account(address).await.unwrap();
+        assert!(account.is_none());
+    }
+
+    // The campaign can be configured again
+    create_config(&mut context, Pubkey::new_unique(), DEFAULT_CAMPAIGN_ID).await;
+
+    // NFT records of version 0 belong to the closed config and can't be migrated anymore
+    let nft_record = nft_pda(DEFAULT_CAMPAIGN_ID, &Pubkey::new_unique()).0;
+    let data = [8000, 40000, 5000].map(i32::to_le_bytes).concat();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let mut account = AccountSharedData::new(
+        rent.minimum_balance(data.len()),
+        data.len(),
+        &gaimin_staking::ID,
+    );
+    account.set_data_from_slice(&data);
+    context.set_account(&nft_record, &account);
+
+    let migrate = Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(authority, true),
+            AccountMeta::new(nft_record, false),
+            AccountMeta::new(config, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: GaiminInstruction::Migrate.pack(),
+    };
+    let result = process(&mut context, migrate, &[]).await;
+    assert_error(result, GaiminError::PermissionDenied);
+}
+
+#[tokio::test]
+async fn multisig_authority() {
+    let mut context = program_test().start_with_context().await;
+    create_config(&mut context, Pubkey::new_unique(), DEFAULT_CAMPAIGN_ID).await;
+
+    let authority = context.payer.pubkey();
+    let signers = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let multisig = Pubkey::find_program_address(&[MULTISIG_PDA_SEED], &gaimin_staking::ID).0;
+
+    let set_multisig = Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(authority, true),
+            AccountMeta::new(multisig, false),
+            AccountMeta::new_readonly(config_pda(DEFAULT_CAMPAIGN_ID).0, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: GaiminInstruction::SetMultisig(SetMultisigArgs {
+            threshold: 2,
+            signers: signers.iter().map(|signer| signer.pubkey()).collect(),
+        })
+        .pack(),
+    };
+    process(&mut context, set_multisig, &[]).await.unwrap();
+
+    let propose = Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new_readonly(multisig, false),
+            AccountMeta::new(config_pda(DEFAULT_CAMPAIGN_ID).0, false),
+        ],
+        data: GaiminInstruction::ProposeAuthority.pack(),
+    };
+    process(&mut context, propose, &[]).await.unwrap();
+
+    let with_signers = |mut instruction: Instruction, signers: &[&Keypair]| {
+        instruction.accounts.extend(
+            signers
+                .iter()
+                .map(|signer| AccountMeta::new_readonly(signer.pubkey(), true)),
+        );
+        instruction
+    };
+
+    let accept = Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(multisig, false),
+            AccountMeta::new(config_pda(DEFAULT_CAMPAIGN_ID).0, false),
+        ],
+        data: GaiminInstruction::AcceptAuthority.pack(),
+    };
+    process(
+        &mut context,
+        with_signers(accept, &[&signers[0], &signers[2]]),
+        &[&signers[0], &signers[2]],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_config(&mut context, DEFAULT_CAMPAIGN_ID)
+            .await
+            .authority,
+        multisig
     );
 
-    context.banks_client.process_transaction(transaction).await.unwrap();
+    // The multisig can't be closed while it is the authority
+    let close_multisig = Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(multisig, false),
+            AccountMeta::new(multisig, false),
+            AccountMeta::new(authority, false),
+            AccountMeta::new_readonly(config_pda(DEFAULT_CAMPAIGN_ID).0, false),
+        ],
+        data: GaiminInstruction::CloseMultisig.pack(),
+    };
+    let result = process(
+        &mut context,
+        with_signers(close_multisig, &[&signers[0], &signers[1]]),
+        &[&signers[0], &signers[1]],
+    )
+    .await;
+    assert_error(result, GaiminError::AccountInUse);
+
+    let set_pause = Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(multisig, false),
+            AccountMeta::new(config_pda(DEFAULT_CAMPAIGN_ID).0, false),
+        ],
+        data: GaiminInstruction::SetPause(SetPauseArgs {
+            paused: PAUSE_CLAIM,
+        })
+        .pack(),
+    };
+
+    // The same signer passed twice doesn't count twice
+    let result = process(
+        &mut context,
+        with_signers(set_pause.clone(), &[&signers[1], &signers[1]]),
+        &[&signers[1]],
+    )
+    .await;
+    assert_error(result, GaiminError::PermissionDenied);
+
+    process(
+        &mut context,
+        with_signers(set_pause, &[&signers[1], &signers[2]]),
+        &[&signers[1], &signers[2]],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_config(&mut context, DEFAULT_CAMPAIGN_ID).await.paused,
+        PAUSE_CLAIM
+    );
+}
+
+#[tokio::test]
+async fn epoch_schedule() {
+    let mut context = program_test().start_with_context().await;
+    create_config(&mut context, Pubkey::new_unique(), DEFAULT_CAMPAIGN_ID).await;
+
+    let authority = context.payer.pubkey();
+    let epoch_schedule = Pubkey::find_program_address(
+        &[
+            EPOCH_SCHEDULE_PDA_SEED,
+            campaign_seed(&DEFAULT_CAMPAIGN_ID.to_le_bytes()),
+        ],
+        &gaimin_staking::ID,
+    )
+    .0;
+    let set_epoch_schedule = |epochs: Vec<Epoch>| Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(authority, true),
+            AccountMeta::new(epoch_schedule, false),
+            AccountMeta::new_readonly(config_pda(DEFAULT_CAMPAIGN_ID).0, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: GaiminInstruction::SetEpochSchedule(SetEpochScheduleArgs { epochs }).pack(),
+    };
+
+    let now = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp;
+    let first = Epoch {
+        starts_at: now + TIMELOCK_DURATION + 100,
+        rate_bps: 20_000,
+        initial_bonus: 50,
+    };
+    let second = Epoch {
+        starts_at: now + TIMELOCK_DURATION + 200,
+        rate_bps: 5_000,
+        initial_bonus: 0,
+    };
+
+    // Epochs must be ordered
+    let result = process(&mut context, set_epoch_schedule(vec![second, first]), &[]).await;
+    assert_error(result, GaiminError::InvalidConfig);
+
+    // Epochs must start after the timelock
+    let result = process(
+        &mut context,
+        set_epoch_schedule(vec![Epoch {
+            starts_at: now + 100,
+            ..first
+        }]),
+        &[],
+    )
+    .await;
+    assert_error(result, GaiminError::InvalidConfig);
+
+    process(&mut context, set_epoch_schedule(vec![first]), &[])
+        .await
+        .unwrap();
+
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp += 150;
+    context.set_sysvar(&clock);
+
+    // The first epoch starts within the timelock and can't be changed anymore
+    let result = process(
+        &mut context,
+        set_epoch_schedule(vec![Epoch {
+            rate_bps: 30_000,
+            ..first
+        }]),
+        &[],
+    )
+    .await;
+    assert_error(result, GaiminError::InvalidConfig);
+
+    let result = process(&mut context, set_epoch_schedule(vec![second]), &[]).await;
+    assert_error(result, GaiminError::InvalidConfig);
+
+    // New epochs must still start after the timelock
+    let result = process(
+        &mut context,
+        set_epoch_schedule(vec![
+            first,
+            Epoch {
+                starts_at: now + TIMELOCK_DURATION + 120,
+                ..second
+            },
+        ]),
+        &[],
+    )
+    .await;
+    assert_error(result, GaiminError::InvalidConfig);
+
+    process(&mut context, set_epoch_schedule(vec![first, second]), &[])
+        .await
+        .unwrap();
+
+    let account = context
+        .banks_client
+        .get_account(epoch_schedule)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        EpochSchedule::unpack_unchecked(&account.data)
+            .unwrap()
+            .epochs,
+        vec![first, second]
+    );
+
+    // A started schedule is kept while the campaign has NFT records
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp += TIMELOCK_DURATION;
+    context.set_sysvar(&clock);
+
+    let config = config_pda(DEFAULT_CAMPAIGN_ID).0;
+    let mut account = context
+        .banks_client
+        .get_account(config)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut state = Config::unpack(&account.data).unwrap();
+    state.nft_count = 1;
+    Config::pack(state, &mut account.data).unwrap();
+    context.set_account(&config, &account.into());
+
+    let close_epoch_schedule = Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(epoch_schedule, false),
+            AccountMeta::new(authority, false),
+            AccountMeta::new_readonly(config, false),
+        ],
+        data: GaiminInstruction::CloseEpochSchedule.pack(),
+    };
+    let result = process(&mut context, close_epoch_schedule, &[]).await;
+    assert_error(result, GaiminError::AccountInUse);
+}
+
+#[tokio::test]
+async fn migrate() {
+    let mut context = program_test().start_with_context().await;
+    let authority = context.payer.pubkey();
+    let creator = Pubkey::new_unique();
+    let rent = context.banks_client.get_rent().await.unwrap();
+
+    let legacy_account = |data: Vec<u8>| {
+        let mut account = AccountSharedData::new(
+            rent.minimum_balance(data.len()),
+            data.len(),
+            &gaimin_staking::ID,
+        );
+        account.set_data_from_slice(&data);
+        account
+    };
+
+    // Config, NFT record and claim record deployed before campaigns, with `i32` times and amounts
+    let mut config = [authority, creator].map(|key| key.to_bytes()).concat();
+    for value in [1000, 32000, 8000, 2, 300] {
+        config.extend_from_slice(&i32::to_le_bytes(value));
+    }
+    assert_eq!(config.len(), Config::LEGACY_LEN);
+    context.set_account(&config_pda(DEFAULT_CAMPAIGN_ID).0, &legacy_account(config));
+
+    let [mint, unmigrated_mint] = [(); 2].map(|_| Pubkey::new_unique());
+    let nft_record = nft_pda(DEFAULT_CAMPAIGN_ID, &mint).0;
+    let data = [8000, 40000, 5000].map(i32::to_le_bytes).concat();
+    assert_eq!(data.len(), NftRecord::LEGACY_LEN);
+    context.set_account(&nft_record, &legacy_account(data.clone()));
+    context.set_account(
+        &nft_pda(DEFAULT_CAMPAIGN_ID, &unmigrated_mint).0,
+        &legacy_account(data),
+    );
+
+    let wallet = Pubkey::new_unique();
+    let claim = claim_pda(wallet, [7; 32], DEFAULT_CAMPAIGN_ID).0;
+    let mut data = [5, 1200].map(i32::to_le_bytes).concat();
+    data.extend_from_slice(wallet.as_ref());
+    data.extend_from_slice(&[b'a'; BNB_CHAIN_WALLET_ADDRESS_LENGTH]);
+    assert_eq!(data.len(), ClaimRecord::LEGACY_LEN);
+    context.set_account(&claim, &legacy_account(data));
+
+    let migrate = |target: Pubkey| Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(authority, true),
+            AccountMeta::new(target, false),
+            AccountMeta::new(config_pda(DEFAULT_CAMPAIGN_ID).0, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: GaiminInstruction::Migrate.pack(),
+    };
+
+    process(
+        &mut context,
+        migrate(config_pda(DEFAULT_CAMPAIGN_ID).0),
+        &[],
+    )
+    .await
+    .unwrap();
+    process(&mut context, migrate(nft_record), &[])
+        .await
+        .unwrap();
+    process(&mut context, migrate(claim), &[]).await.unwrap();
+
+    let config = get_config(&mut context, DEFAULT_CAMPAIGN_ID).await;
+    assert_eq!(config.campaign_id, DEFAULT_CAMPAIGN_ID);
+    assert_eq!(config.authority, authority);
+    assert_eq!(config.creator, creator);
+    assert_eq!(config.claimable_from, 1000);
+    assert_eq!(config.claimable_until, i64::MAX);
+    assert_eq!(config.accumulated_reward, 32000);
+    assert_eq!(config.initial_reward, 8000);
+    assert_eq!(config.accumulation_duration, 2);
+    assert_eq!(config.generation_duration, 300);
+    assert_eq!(config.timelock_duration, 0);
+    assert_eq!(config.reward_decimals, 0);
+    assert_eq!(config.nft_count, 1);
+    assert_eq!(config.allocated_reward, 32000);
+
+    let account = context
+        .banks_client
+        .get_account(claim)
+        .await
+        .unwrap()
+        .unwrap();
+    let claim = ClaimRecord::unpack(&account.data).unwrap();
+    assert_eq!(claim.generation, 5);
+    assert_eq!(claim.amount, 1200);
+    assert_eq!(claim.owner, wallet);
+    assert_eq!(claim.campaign_id, DEFAULT_CAMPAIGN_ID);
+
+    let account = context
+        .banks_client
+        .get_account(nft_record)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.lamports, rent.minimum_balance(NftRecord::LEN));
+    let nft_record = NftRecord::unpack(&account.data).unwrap();
+    assert_eq!(nft_record.claimed_amount, 8000);
+    assert_eq!(nft_record.total_amount, 40000);
+    assert_eq!(nft_record.last_claim_at, 5000);
+
+    // Only the allocation of migrated records is released when they are closed
+    let close = |mint: Pubkey| Instruction {
+        program_id: gaimin_staking::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(nft_pda(DEFAULT_CAMPAIGN_ID, &mint).0, false),
+            AccountMeta::new(authority, false),
+            AccountMeta::new(config_pda(DEFAULT_CAMPAIGN_ID).0, false),
+        ],
+        data: GaiminInstruction::CloseNftRecord.pack(),
+    };
+    process(&mut context, close(unmigrated_mint), &[])
+        .await
+        .unwrap();
+    let config = get_config(&mut context, DEFAULT_CAMPAIGN_ID).await;
+    assert_eq!(config.nft_count, 1);
+    assert_eq!(config.allocated_reward, 32000);
+
+    process(&mut context, close(mint), &[]).await.unwrap();
+    let config = get_config(&mut context, DEFAULT_CAMPAIGN_ID).await;
+    assert_eq!(config.nft_count, 0);
+    assert_eq!(config.allocated_reward, 0);
+
+    // Accounts in the current layout are rejected
+    let result = process(
+        &mut context,
+        migrate(config_pda(DEFAULT_CAMPAIGN_ID).0),
+        &[],
+    )
+    .await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData),
+    );
 }
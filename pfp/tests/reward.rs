@@ -0,0 +1,511 @@
+use gaimin_staking::{
+    reward::{
+        accrue, accumulated, checkpoint, is_accrual_finished, remaining_budget, reward_index,
+        Accrual,
+    },
+    state::{
+        BoostSchedule, BoostTier, Config, Epoch, NftRecord, RewardCurve, RewardTier,
+        MAX_INDEX_CHECKPOINTS, REWARD_INDEX_SCALE,
+    },
+};
+use solana_program::pubkey::Pubkey;
+
+fn config(accumulation_duration: i64, accrual_ends_at: i64) -> Config {
+    Config {
+        campaign_id: 0,
+        authority: Pubkey::new_unique(),
+        pending_authority: Pubkey::default(),
+        creator: Pubkey::new_unique(),
+        registrar: Pubkey::default(),
+        settler: Pubkey::default(),
+        claimable_from: 1000,
+        claimable_until: i64::MAX,
+        accrual_ends_at,
+        accumulated_reward: 32000,
+        initial_reward: 8000,
+        accumulation_duration,
+        generation_duration: 300,
+        timelock_duration: 0,
+        reward_decimals: 2,
+        paused: 0,
+        nft_count: 0,
+        payer: Pubkey::default(),
+        reward_curve: RewardCurve::Linear,
+        emission_budget: 0,
+        allocated_reward: 0,
+        claimed_reward: 0,
+        boost_schedule: BoostSchedule::default(),
+        reward_index: 0,
+        index_updated_at: 0,
+        emission_rate: 0,
+        staked_count: 0,
+        index_history: vec![],
+    }
+}
+
+fn nft_record(config: &Config) -> NftRecord {
+    NftRecord {
+        claimed_amount: 0,
+        total_amount: config.initial_reward + config.accumulated_reward,
+        last_claim_at: config.claimable_from,
+        payer: Pubkey::default(),
+        staked_since: config.claimable_from,
+        staker: Pubkey::default(),
+        reward_index: Some(0),
+        reward_remainder: 0,
+        pending_reward: 0,
+    }
+}
+
+fn settle(nft_record: &mut NftRecord, accrual: Accrual) {
+    nft_record.claimed_amount += accrual.reward;
+    nft_record.last_claim_at = accrual.last_claim_at;
+    nft_record.reward_index = Some(accrual.reward_index);
+    nft_record.reward_remainder = accrual.reward_remainder;
+}
+
+/// Returns the reward accrued by an NFT staked since `staked_since` from the start of claiming
+/// until `at`, excluding the initial reward
+fn earned(config: &Config, epochs: &[Epoch], staked_since: i64, at: i64) -> u64 {
+    let mut nft_record = nft_record(config);
+    nft_record.claimed_amount = 1;
+    nft_record.total_amount = u64::MAX;
+    nft_record.staked_since = staked_since;
+    accrue(config, epochs, &nft_record, at).reward
+}
+
+/// Claims at every timestamp in `claims` and returns the total payout
+fn claim_at(config: &Config, epochs: &[Epoch], claims: impl IntoIterator<Item = i64>) -> u64 {
+    let mut nft_record = nft_record(config);
+    for now in claims {
+        if is_accrual_finished(config, epochs, &nft_record) {
+            break;
+        }
+
+        let accrual = accrue(config, epochs, &nft_record, now);
+        settle(&mut nft_record, accrual);
+    }
+
+    nft_record.claimed_amount
+}
+
+#[test]
+fn remainder_carries_over() {
+    let config = config(7, 0);
+    let mut nft_record = nft_record(&config);
+
+    let accrual = accrue(&config, &[], &nft_record, 1010);
+    assert_eq!(accrual.reward, 8001);
+    assert_eq!(accrual.last_claim_at, 1010);
+    settle(&mut nft_record, accrual);
+
+    // 3 seconds carried over from the first claim complete the next period
+    let accrual = accrue(&config, &[], &nft_record, 1014);
+    assert_eq!(accrual.reward, 1);
+    assert_eq!(accrual.last_claim_at, 1014);
+}
+
+#[test]
+fn pending_reward_counts_as_settled() {
+    let config = config(7, 0);
+    let mut nft_record = nft_record(&config);
+    nft_record.pending_reward = 8001;
+
+    // The initial reward has been settled on unstaking
+    let accrual = accrue(&config, &[], &nft_record, 1014);
+    assert_eq!(accrual.reward, 2);
+
+    // The pending reward is part of the allowance of the NFT
+    nft_record.pending_reward = nft_record.total_amount - 1;
+    let accrual = accrue(&config, &[], &nft_record, 1014);
+    assert_eq!(accrual.reward, 1);
+}
+
+/// Checks that claiming at random intervals pays the same as a single claim at the end
+fn assert_frequency_independent(config: &Config, epochs: &[Epoch]) -> u64 {
+    let accrual_ends_at = config.accrual_ends_at;
+    let expected = claim_at(config, epochs, [accrual_ends_at]);
+
+    // Linear congruential generator, so that failures are reproducible
+    let mut seed = 0x2545_f491_u64;
+    for _ in 0..20 {
+        let mut now = config.claimable_from;
+        let mut claims = vec![];
+        while now < accrual_ends_at + 100 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            now += (seed >> 33) as i64 % 50 + 1;
+            claims.push(now);
+        }
+
+        assert_eq!(claim_at(config, epochs, claims), expected);
+    }
+
+    expected
+}
+
+#[test]
+fn claim_frequency_does_not_change_payout() {
+    let config = config(7, 1000 + 7 * 5000 + 3);
+    assert_eq!(assert_frequency_independent(&config, &[]), 8000 + 5000);
+}
+
+#[test]
+fn payout_is_capped_by_total_amount() {
+    let config = config(1, 0);
+    let now = config.claimable_from + 100_000;
+    assert_eq!(claim_at(&config, &[], [now - 50_000, now]), 40000);
+
+    let mut nft_record = nft_record(&config);
+    nft_record.claimed_amount = nft_record.total_amount;
+    assert_eq!(accrue(&config, &[], &nft_record, now).reward, 0);
+}
+
+#[test]
+fn accrual_finishes_after_last_whole_period() {
+    let config = config(10, 1095);
+    let mut nft_record = nft_record(&config);
+    assert!(!is_accrual_finished(&config, &[], &nft_record));
+
+    let accrual = accrue(&config, &[], &nft_record, 2000);
+    assert_eq!(accrual.reward, 8009);
+    assert_eq!(accrual.last_claim_at, 1095);
+    settle(&mut nft_record, accrual);
+    assert!(is_accrual_finished(&config, &[], &nft_record));
+}
+
+#[test]
+fn cliff_releases_accrued_rewards_at_once() {
+    let mut config = config(10, 0);
+    config.reward_curve = RewardCurve::CliffLinear { cliff: 100 };
+
+    assert_eq!(accumulated(&config, 1000), 0);
+    assert_eq!(accumulated(&config, 1099), 0);
+    assert_eq!(accumulated(&config, 1100), 10);
+    assert_eq!(accumulated(&config, 1155), 15);
+
+    config.accrual_ends_at = 1000 + 10 * 2000 + 7;
+    assert_eq!(assert_frequency_independent(&config, &[]), 8000 + 2000);
+}
+
+#[test]
+fn decaying_rate_halves_every_half_life() {
+    let mut config = config(10, 0);
+    config.reward_curve = RewardCurve::ExponentialDecay { half_life: 1000 };
+
+    assert_eq!(accumulated(&config, 1500), 50);
+    assert_eq!(accumulated(&config, 2000), 100);
+    assert_eq!(accumulated(&config, 3000), 150);
+    assert_eq!(accumulated(&config, 4000), 175);
+    assert_eq!(accumulated(&config, 4500), 181);
+
+    // Converges to twice the reward of the first half-life
+    assert_eq!(accumulated(&config, i64::MAX), 199);
+
+    config.accrual_ends_at = 1000 + 5000 + 3;
+    assert_eq!(assert_frequency_independent(&config, &[]), 8000 + 193);
+}
+
+#[test]
+fn tiers_accrue_at_their_own_rates() {
+    let mut config = config(10, 0);
+    config.reward_curve = RewardCurve::Tiered(vec![
+        RewardTier {
+            starts_after: 100,
+            rate: 3,
+        },
+        RewardTier {
+            starts_after: 200,
+            rate: 1,
+        },
+        RewardTier {
+            starts_after: 300,
+            rate: 0,
+        },
+    ]);
+
+    assert_eq!(accumulated(&config, 1100), 0);
+    assert_eq!(accumulated(&config, 1105), 1);
+    assert_eq!(accumulated(&config, 1200), 30);
+    assert_eq!(accumulated(&config, 1250), 35);
+    assert_eq!(accumulated(&config, 1300), 40);
+    assert_eq!(accumulated(&config, 5000), 40);
+
+    // Nothing accrues after the last tier, so the accrual is finished
+    let mut nft_record = nft_record(&config);
+    let accrual = accrue(&config, &[], &nft_record, 1400);
+    assert_eq!(accrual.reward, 8040);
+    settle(&mut nft_record, accrual);
+    assert!(is_accrual_finished(&config, &[], &nft_record));
+
+    config.accrual_ends_at = 1000 + 297;
+    assert_eq!(assert_frequency_independent(&config, &[]), 8000 + 39);
+}
+
+#[test]
+fn budget_caps_remaining_payouts() {
+    let mut config = config(1, 0);
+    assert_eq!(remaining_budget(&config), u64::MAX);
+
+    config.emission_budget = 100_000;
+    config.claimed_reward = 99_000;
+    assert_eq!(remaining_budget(&config), 1000);
+
+    // Claims made before the budget was lowered may exceed it
+    config.emission_budget = 50_000;
+    assert_eq!(remaining_budget(&config), 0);
+}
+
+#[test]
+fn boost_applies_after_continuous_staking() {
+    let mut config = config(10, 0);
+    config.boost_schedule = BoostSchedule {
+        tiers: vec![
+            BoostTier {
+                staked_for: 1000,
+                multiplier_bps: 12_500,
+            },
+            BoostTier {
+                staked_for: 3000,
+                multiplier_bps: 15_000,
+            },
+        ],
+    };
+
+    // Staked since claiming opened
+    assert_eq!(earned(&config, &[], 1000, 2000), 100);
+    assert_eq!(earned(&config, &[], 1000, 3000), 100 + 125);
+    assert_eq!(earned(&config, &[], 1000, 5000), 100 + 250 + 150);
+
+    // Staked later, so the tiers are reached later
+    assert_eq!(earned(&config, &[], 2000, 5000), 200 + 250);
+
+    // A new staker starts over without a boost
+    let mut nft_record = nft_record(&config);
+    nft_record.claimed_amount = 8000;
+    nft_record.last_claim_at = 4000;
+    nft_record.reward_index = Some(reward_index(&config, 4000));
+    nft_record.staked_since = 4000;
+    assert_eq!(accrue(&config, &[], &nft_record, 5000).reward, 100);
+
+    config.accrual_ends_at = 1000 + 10 * 900 + 3;
+    assert_eq!(
+        assert_frequency_independent(&config, &[]),
+        8000 + 100 + 250 + 600 * 3 / 2
+    );
+}
+
+#[test]
+fn epochs_accrue_at_their_own_rates() {
+    let config = config(10, 1000 + 10 * 900 + 3);
+    let epochs = [
+        Epoch {
+            starts_at: 2000,
+            rate_bps: 20_000,
+            initial_bonus: 50,
+        },
+        Epoch {
+            starts_at: 4000,
+            rate_bps: 5_000,
+            initial_bonus: 0,
+        },
+        Epoch {
+            starts_at: 6000,
+            rate_bps: 10_000,
+            initial_bonus: 30,
+        },
+    ];
+
+    // The base rate applies before the first epoch, and bonuses once the epoch has started
+    assert_eq!(earned(&config, &epochs, 1000, 2000), 100);
+    assert_eq!(earned(&config, &epochs, 1000, 3000), 100 + 200 + 50);
+    assert_eq!(earned(&config, &epochs, 1000, 5000), 100 + 400 + 50 + 50);
+    assert_eq!(
+        earned(&config, &epochs, 1000, 7000),
+        100 + 400 + 100 + 100 + 50 + 30
+    );
+
+    // A claim spanning several epochs pays each part at its own rate
+    let mut nft_record = nft_record(&config);
+    nft_record.claimed_amount = 8000;
+    nft_record.last_claim_at = 3000;
+    nft_record.reward_index = Some(reward_index(&config, 3000));
+    let accrual = accrue(&config, &epochs, &nft_record, 6500);
+    assert_eq!(accrual.reward, 200 + 100 + 50 + 30);
+    assert_eq!(accrual.last_claim_at, 6500);
+
+    // Bonuses are paid once however often the NFT is claimed
+    assert_eq!(
+        assert_frequency_independent(&config, &epochs),
+        8000 + 100 + 400 + 100 + 400 + 50 + 30
+    );
+}
+
+#[test]
+fn rate_changes_apply_after_checkpoint() {
+    let mut config = config(10, 0);
+    config.boost_schedule = BoostSchedule {
+        tiers: vec![BoostTier {
+            staked_for: 1000,
+            multiplier_bps: 15_000,
+        }],
+    };
+
+    let mut settled = nft_record(&config);
+    settled.claimed_amount = 8000;
+    let accrual = accrue(&config, &[], &settled, 2000);
+    settle(&mut settled, accrual);
+    assert_eq!(settled.claimed_amount, 8000 + 100);
+
+    // The rate doubles at 3000
+    checkpoint(&mut config, 3000).unwrap();
+    config.accumulation_duration = 5;
+    assert_eq!(config.reward_index, 200 * REWARD_INDEX_SCALE);
+    assert_eq!(accumulated(&config, 4000), 200 + 200);
+
+    // Time before the change keeps the old rate, whenever the NFT last claimed
+    let accrual = accrue(&config, &[], &settled, 4000);
+    assert_eq!(accrual.reward, 150 + 300);
+
+    let mut unclaimed = nft_record(&config);
+    unclaimed.claimed_amount = 8000;
+    let accrual = accrue(&config, &[], &unclaimed, 4000);
+    assert_eq!(accrual.reward, 100 + 150 + 300);
+    settle(&mut unclaimed, accrual);
+    assert_eq!(accrue(&config, &[], &unclaimed, 5000).reward, 300);
+
+    // Records created before the index existed are settled at the current rate
+    let mut legacy = nft_record(&config);
+    legacy.claimed_amount = 8000;
+    legacy.last_claim_at = 3000;
+    legacy.reward_index = None;
+    assert_eq!(accrue(&config, &[], &legacy, 4000).reward, 300);
+}
+
+#[test]
+fn multipliers_weigh_each_checkpointed_rate() {
+    let mut config = config(10, 0);
+    config.boost_schedule = BoostSchedule {
+        tiers: vec![BoostTier {
+            staked_for: 1500,
+            multiplier_bps: 15_000,
+        }],
+    };
+    let epochs = [Epoch {
+        starts_at: 3000,
+        rate_bps: 20_000,
+        initial_bonus: 0,
+    }];
+
+    let mut nft_record = nft_record(&config);
+    nft_record.claimed_amount = 8000;
+
+    // The rate doubles at 2000 and is restored at 4000, while the boost starts at 2500 and the
+    // epoch at 3000
+    checkpoint(&mut config, 2000).unwrap();
+    config.accumulation_duration = 5;
+    checkpoint(&mut config, 4000).unwrap();
+    config.accumulation_duration = 10;
+    assert_eq!(config.index_history.len(), 2);
+
+    let accrual = accrue(&config, &epochs, &nft_record, 5000);
+    assert_eq!(accrual.reward, 100 + 100 + 150 + 200 * 3 + 100 * 3);
+}
+
+#[test]
+fn checkpoints_keep_the_shape_of_the_curve() {
+    let config = || {
+        let mut config = config(10, 0);
+        config.reward_curve = RewardCurve::CliffLinear { cliff: 1500 };
+        config.boost_schedule = BoostSchedule {
+            tiers: vec![BoostTier {
+                staked_for: 1800,
+                multiplier_bps: 20_000,
+            }],
+        };
+        config
+    };
+
+    // The cliff is released at 2500 and the boost starts at 2800
+    assert_eq!(earned(&config(), &[], 1000, 4000), 180 + 120 * 2);
+
+    // Checkpoints that don't change the rate don't change the payout
+    let mut unchanged = config();
+    checkpoint(&mut unchanged, 2000).unwrap();
+    checkpoint(&mut unchanged, 4000).unwrap();
+    assert_eq!(unchanged.index_history.len(), 1);
+    assert_eq!(earned(&unchanged, &[], 1000, 4000), 180 + 120 * 2);
+
+    // The rate doubles at 3000
+    let mut doubled = config();
+    checkpoint(&mut doubled, 2000).unwrap();
+    checkpoint(&mut doubled, 3000).unwrap();
+    doubled.accumulation_duration = 5;
+    assert_eq!(earned(&doubled, &[], 1000, 4000), 180 + 20 * 2 + 200 * 2);
+
+    // Every rate is kept, so the rate can't change once the history is full
+    for at in 1..MAX_INDEX_CHECKPOINTS as i64 {
+        checkpoint(&mut doubled, 3000 + at).unwrap();
+        doubled.accumulation_duration = 5 + at;
+    }
+    assert_eq!(doubled.index_history.len(), MAX_INDEX_CHECKPOINTS);
+    checkpoint(&mut doubled, 4000).unwrap_err();
+}
+
+#[test]
+fn pool_is_shared_by_staked_nfts() {
+    let mut config = config(10, 0);
+    config.emission_rate = 90;
+
+    // NFTs join the pool at 1000, 2000 and 3000
+    let mut nft_records = vec![];
+    for now in [1000, 2000, 3000] {
+        checkpoint(&mut config, now).unwrap();
+        config.staked_count += 1;
+
+        let mut nft_record = nft_record(&config);
+        nft_record.claimed_amount = 1;
+        nft_record.total_amount = u64::MAX;
+        nft_record.last_claim_at = now;
+        nft_record.reward_index = Some(reward_index(&config, now));
+        nft_records.push(nft_record);
+    }
+
+    let rewards: Vec<u64> = nft_records
+        .iter()
+        .map(|nft_record| accrue(&config, &[], nft_record, 4000).reward)
+        .collect();
+    assert_eq!(rewards, [90_000 + 45_000 + 30_000, 45_000 + 30_000, 30_000]);
+    assert_eq!(rewards.iter().sum::<u64>(), 3000 * 90);
+
+    // Nothing is emitted while no NFTs are staked
+    checkpoint(&mut config, 4000).unwrap();
+    config.staked_count = 0;
+    assert_eq!(reward_index(&config, 5000), config.reward_index);
+}
+
+#[test]
+fn index_stops_at_accrual_end() {
+    let mut config = config(10, 3000);
+    config.emission_rate = 90;
+    checkpoint(&mut config, 1000).unwrap();
+    config.staked_count = 1;
+
+    let mut staked = nft_record(&config);
+    staked.claimed_amount = 1;
+    staked.total_amount = u64::MAX;
+    staked.reward_index = Some(reward_index(&config, 1000));
+
+    // Another NFT joins the pool after the end of accrual
+    checkpoint(&mut config, 4000).unwrap();
+    config.staked_count += 1;
+    assert_eq!(reward_index(&config, 5000), 2000 * 90 * REWARD_INDEX_SCALE);
+    assert_eq!(accrue(&config, &[], &staked, 5000).reward, 2000 * 90);
+
+    // The rate changes after the end of accrual outside of pool mode
+    let mut config = self::config(10, 3000);
+    checkpoint(&mut config, 4000).unwrap();
+    config.accumulation_duration = 5;
+    assert_eq!(earned(&config, &[], 1000, 5000), 200);
+}
@@ -1,12 +1,243 @@
-use gaimin_staking::processor::CONFIG_PDA_SEED;
+use gaimin_staking::{
+    error::GaiminError,
+    processor::{
+        campaign_seed, Processor, CONFIG_PDA_SEED, MPL_TOKEN_METADATA_PROGRAM_ID, NFT_PDA_SEED,
+        SPL_ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID,
+    },
+    state::NftRecord,
+};
+use mpl_token_metadata::types::{Key, TokenDelegateRole, TokenState};
 use solana_program_test::*;
 
-use solana_program::pubkey::Pubkey;
+use solana_program::{
+    account_info::AccountInfo,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    entrypoint::ProgramResult,
+    instruction::{Instruction, InstructionError},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+};
+use solana_sdk::{
+    account::{AccountSharedData, WritableAccount},
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+use spl_token::state::{Account, AccountState};
+
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // The processor requires the account slice to live as long as the accounts themselves
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    Processor::process(program_id, accounts, instruction_data)
+}
+
+/// Stands in for the Token Metadata program in delegating, locking, unlocking and revoking, which
+/// only updates the token record
+fn process_token_metadata(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (authority, token_record) = match instruction_data[0] {
+        // Delegate and revoke
+        44 | 45 => (&accounts[7], &accounts[4]),
+        // Lock and unlock
+        46 | 47 => (&accounts[0], &accounts[6]),
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut data = token_record.try_borrow_mut_data()?;
+    let locked = data[2] == TokenState::Locked as u8;
+    let delegated_to_authority = data[4] == 1 && data[5..37] == authority.key.to_bytes();
+    match instruction_data[0] {
+        44 if !locked => {
+            data[4] = 1;
+            data[5..37].copy_from_slice(&accounts[1].key.to_bytes());
+            data[37..39].copy_from_slice(&[1, TokenDelegateRole::Staking as u8]);
+        }
+        45 if !locked => data[4..].fill(0),
+        46 if delegated_to_authority => data[2] = TokenState::Locked as u8,
+        47 if delegated_to_authority => data[2] = TokenState::Unlocked as u8,
+        _ => return Err(ProgramError::InvalidAccountData),
+    }
+    Ok(())
+}
 
 pub fn program_test() -> ProgramTest {
-    ProgramTest::new("gaimin_staking", gaimin_staking::ID, None)
+    let mut program_test = ProgramTest::new(
+        "gaimin_staking",
+        gaimin_staking::ID,
+        processor!(process_instruction),
+    );
+    program_test.add_program(
+        "mpl_token_metadata",
+        MPL_TOKEN_METADATA_PROGRAM_ID,
+        processor!(process_token_metadata),
+    );
+    program_test
+}
+
+pub fn config_pda(campaign_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[CONFIG_PDA_SEED, campaign_seed(&campaign_id.to_le_bytes())],
+        &gaimin_staking::ID,
+    )
+}
+
+pub fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(
+        &[gaimin_staking::ID.as_ref()],
+        &bpf_loader_upgradeable::id(),
+    )
+    .0
+}
+
+/// Installs a program data account that makes `upgrade_authority` the upgrade authority of the
+/// program, since builtin programs don't have one
+pub fn set_upgrade_authority(context: &mut ProgramTestContext, upgrade_authority: &Pubkey) {
+    let len = UpgradeableLoaderState::size_of_programdata_metadata();
+    let mut account = AccountSharedData::new(
+        Rent::default().minimum_balance(len),
+        len,
+        &bpf_loader_upgradeable::id(),
+    );
+
+    // Bincode encoding of `UpgradeableLoaderState::ProgramData`
+    let data = account.data_as_mut_slice();
+    data[0..4].copy_from_slice(&3u32.to_le_bytes());
+    data[12] = 1;
+    data[13..45].copy_from_slice(&upgrade_authority.to_bytes());
+
+    context.set_account(&program_data_address(), &account);
+}
+
+pub fn nft_pda(campaign_id: u64, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            NFT_PDA_SEED,
+            campaign_seed(&campaign_id.to_le_bytes()),
+            &mint.to_bytes(),
+        ],
+        &gaimin_staking::ID,
+    )
+}
+
+pub fn token_pda(owner: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            &owner.to_bytes(),
+            &spl_token::ID.to_bytes(),
+            &mint.to_bytes(),
+        ],
+        &SPL_ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID,
+    )
+}
+
+pub fn token_record_pda(mint: &Pubkey, token: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            &MPL_TOKEN_METADATA_PROGRAM_ID.to_bytes(),
+            &mint.to_bytes(),
+            b"token_record",
+            &token.to_bytes(),
+        ],
+        &MPL_TOKEN_METADATA_PROGRAM_ID,
+    )
+}
+
+/// Installs an NFT record for `mint`, since registration requires the Token Metadata program
+pub fn set_nft_record(
+    context: &mut ProgramTestContext,
+    campaign_id: u64,
+    mint: &Pubkey,
+    nft_record: NftRecord,
+) {
+    let mut account = AccountSharedData::new(
+        Rent::default().minimum_balance(NftRecord::LEN),
+        NftRecord::LEN,
+        &gaimin_staking::ID,
+    );
+    NftRecord::pack(nft_record, account.data_as_mut_slice()).unwrap();
+
+    context.set_account(&nft_pda(campaign_id, mint).0, &account);
+}
+
+/// Installs the associated token account of `owner` holding the NFT and its token record in
+/// `state` with the given delegate
+pub fn set_token(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    state: TokenState,
+    delegate: Option<(Pubkey, TokenDelegateRole)>,
+) {
+    let token = token_pda(owner, mint).0;
+    let mut account = AccountSharedData::new(
+        Rent::default().minimum_balance(Account::LEN),
+        Account::LEN,
+        &spl_token::ID,
+    );
+    let token_account = Account {
+        mint: *mint,
+        owner: *owner,
+        amount: 1,
+        state: if state == TokenState::Unlocked {
+            AccountState::Initialized
+        } else {
+            AccountState::Frozen
+        },
+        ..Default::default()
+    };
+    Account::pack(token_account, account.data_as_mut_slice()).unwrap();
+    context.set_account(&token, &account);
+
+    // Borsh encoding of `TokenRecord` without a rule set revision
+    let (token_record, bump) = token_record_pda(mint, &token);
+    let mut account = AccountSharedData::new(
+        Rent::default().minimum_balance(80),
+        80,
+        &MPL_TOKEN_METADATA_PROGRAM_ID,
+    );
+    let data = account.data_as_mut_slice();
+    data[0..4].copy_from_slice(&[Key::TokenRecord as u8, bump, state as u8, 0]);
+    if let Some((delegate, role)) = delegate {
+        data[4] = 1;
+        data[5..37].copy_from_slice(&delegate.to_bytes());
+        data[37..39].copy_from_slice(&[1, role as u8]);
+    }
+    context.set_account(&token_record, &account);
+}
+
+pub async fn process(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    // A new blockhash keeps retried instructions from being rejected as duplicate transactions
+    let blockhash = context.get_new_latest_blockhash().await?;
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &[&[&context.payer], signers].concat(),
+        blockhash,
+    );
+
+    context.banks_client.process_transaction(transaction).await
 }
 
-pub fn config_pda() -> (Pubkey, u8) {
-    Pubkey::find_program_address(&[CONFIG_PDA_SEED], &gaimin_staking::ID)
+pub fn assert_error(result: Result<(), BanksClientError>, error: GaiminError) {
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(error as u32)),
+    );
 }